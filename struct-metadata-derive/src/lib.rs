@@ -36,8 +36,8 @@ pub fn derive(input: TokenStream) -> TokenStream {
 
     // ident will refer to the TYPE NAME, outer_name will refer to the presented name in metadata for the type
     let outer_name = match serde_attrs.rename {
-        Some(new_name) => quote!(#new_name),
-        None => quote_spanned!(ident.span() => stringify!(#ident)),
+        Some(new_name) => quote!(::std::borrow::Cow::Borrowed(#new_name)),
+        None => quote_spanned!(ident.span() => ::std::borrow::Cow::Borrowed(stringify!(#ident))),
     };
 
     match data {
@@ -50,7 +50,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
                     let mut flattened_metadata = vec![];
 
                     for field in &fields.named {
-                        let SerdeFieldAttrs {rename, flatten, mut has_default, mut aliases } = _parse_serde_field_attrs(&field.attrs);
+                        let SerdeFieldAttrs {rename, flatten, mut has_default, mut aliases, skip_serializing, skip_deserializing } = _parse_serde_field_attrs(&field.attrs);
                         has_default |= serde_attrs.has_default;
                         let name = field.ident.clone().unwrap();
                         let ty = &field.ty;
@@ -74,14 +74,14 @@ pub fn derive(input: TokenStream) -> TokenStream {
                             flattened_children.push(ty);
                             flattened_metadata.push(metadata);
                         } else {
-                            children.push(quote!{struct_metadata::Entry::<#metadata_type> {
-                                label: #name,
-                                docs: #docs,
-                                metadata: #metadata,
-                                type_info: #ty,
-                                has_default: #has_default,
-                                aliases: &[#(#aliases),*]
-                            }});
+                            children.push(quote!{struct_metadata::Entry::<#metadata_type>::new(#name, #ty)
+                                .with_docs(#docs)
+                                .with_metadata(#metadata)
+                                .with_default(#has_default)
+                                .with_aliases([#(#aliases),*])
+                                .with_skip_serializing(#skip_serializing)
+                                .with_skip_deserializing(#skip_deserializing)
+                            });
                         }
                     }
 
@@ -143,7 +143,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
                 let name = variant.ident.clone();
                 let docs = parse_doc_comment(&variant.attrs);
                 let metadata: proc_macro2::TokenStream = parse_metadata_params(&metadata_type, &variant.attrs);
-                let SerdeFieldAttrs {rename, flatten: _, has_default: _, mut aliases } = _parse_serde_field_attrs(&variant.attrs);
+                let SerdeFieldAttrs {rename, mut aliases, .. } = _parse_serde_field_attrs(&variant.attrs);
 
                 let name = if let Some(name) = rename {
                     aliases.insert(0, name.clone());
@@ -158,10 +158,10 @@ pub fn derive(input: TokenStream) -> TokenStream {
                 };
 
                 all_variants.push(quote!{struct_metadata::Variant::<#metadata_type> {
-                    label: #name,
+                    label: ::std::borrow::Cow::Borrowed(#name),
                     docs: #docs,
                     metadata: #metadata,
-                    aliases: &[#(#aliases),*]
+                    aliases: vec![#(::std::borrow::Cow::Borrowed(#aliases)),*]
                 }});
             }
 
@@ -227,10 +227,10 @@ pub fn derive_enum_string(input: TokenStream) -> TokenStream {
                 };
 
                 all_variants.push(quote!{struct_metadata::Variant::<#metadata_type> {
-                    label: #name,
+                    label: ::std::borrow::Cow::Borrowed(#name),
                     docs: #docs,
                     metadata: #metadata,
-                    aliases: &[#name]
+                    aliases: vec![::std::borrow::Cow::Borrowed(#name)]
                 }});
             }
 
@@ -242,7 +242,7 @@ pub fn derive_enum_string(input: TokenStream) -> TokenStream {
                         struct_metadata::Descriptor::<#metadata_type> {
                             docs: #docs,
                             kind: struct_metadata::Kind::<#metadata_type>::Enum {
-                                name: stringify!(#ident),
+                                name: ::std::borrow::Cow::Borrowed(stringify!(#ident)),
                                 variants: vec![#(#all_variants),*]
                             },
                             metadata: #metadata,
@@ -283,7 +283,7 @@ fn parse_doc_comment(attrs: &[syn::Attribute]) -> proc_macro2::TokenStream {
         quote! { None }
     } else {
         quote!{ Some(vec![
-            #( #lines, )*
+            #( ::std::borrow::Cow::Borrowed(#lines), )*
         ])}
     }
 }
@@ -485,7 +485,11 @@ struct SerdeFieldAttrs {
     /// has a default been defined on this field
     has_default: bool,
     /// other names a field might be labled under
-    aliases: Vec<String>
+    aliases: Vec<String>,
+    /// is this field left out when serializing
+    skip_serializing: bool,
+    /// is this field ignored when deserializing
+    skip_deserializing: bool,
 }
 
 impl syn::parse::Parse for SerdeFieldAttrs {
@@ -521,6 +525,14 @@ impl syn::parse::Parse for SerdeFieldAttrs {
                 out.flatten = true;
             }
 
+            if key == "skip" || key == "skip_serializing" {
+                out.skip_serializing = true;
+            }
+
+            if key == "skip" || key == "skip_deserializing" {
+                out.skip_deserializing = true;
+            }
+
             if input.is_empty() {
                 break
             }
@@ -0,0 +1,88 @@
+//! Build Apache Arrow schemas from descriptors.
+//!
+//! The described type is treated as the row type of a record batch, so the outermost
+//! descriptor must be a struct. Fields that are never serialized are left out. Field metadata
+//! is carried into the Arrow field metadata through the [`ArrowMetadata`] trait.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_schema::{ArrowError, DataType, Field, Fields, Schema, TimeUnit};
+
+use crate::named::{unalias, unquote};
+use crate::{Descriptor, Entry, Kind};
+
+/// Conversion of metadata into the string pairs stored on Arrow fields and schemas
+pub trait ArrowMetadata {
+    /// Key value pairs to attach to the Arrow field
+    fn arrow_metadata(&self) -> HashMap<String, String> { HashMap::new() }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> ArrowMetadata for HashMap<K, V> {
+    fn arrow_metadata(&self) -> HashMap<String, String> {
+        self.iter().map(|(key, value)| (key.as_ref().to_owned(), unquote(value.as_ref()).to_owned())).collect()
+    }
+}
+
+impl<Metadata: Default + ArrowMetadata> Descriptor<Metadata> {
+    /// Build the Arrow schema for a batch of records of this type
+    pub fn arrow_schema(&self) -> Result<Schema, ArrowError> {
+        schema(self)
+    }
+}
+
+/// Build the Arrow schema for a batch of records of the described struct
+pub fn schema<Metadata: Default + ArrowMetadata>(descriptor: &Descriptor<Metadata>) -> Result<Schema, ArrowError> {
+    match &unalias(descriptor).kind {
+        Kind::Struct { children, .. } => Ok(Schema::new_with_metadata(fields(children), descriptor.metadata.arrow_metadata())),
+        _ => Err(ArrowError::SchemaError(format!("{} is not a struct", descriptor.kind.type_expression()))),
+    }
+}
+
+/// Build the Arrow data type for a value along with whether it is nullable
+pub fn data_type<Metadata: Default + ArrowMetadata>(descriptor: &Descriptor<Metadata>) -> (DataType, bool) {
+    let data_type = match &descriptor.kind {
+        Kind::Aliased { kind, .. } => return data_type(kind),
+        Kind::Option(kind) => return (data_type(kind).0, true),
+        Kind::Struct { children, .. } => DataType::Struct(fields(children)),
+        Kind::Sequence(kind) => {
+            let (item, nullable) = data_type(kind);
+            DataType::List(Arc::new(Field::new_list_field(item, nullable)))
+        },
+        Kind::Mapping(key, value) => {
+            let (value, nullable) = data_type(value);
+            let entries = Fields::from(vec![
+                Field::new("keys", data_type(key).0, false),
+                Field::new("values", value, nullable),
+            ]);
+            DataType::Map(Arc::new(Field::new("entries", DataType::Struct(entries), false)), false)
+        },
+        Kind::Enum { .. } => DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+        Kind::DateTime => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        // 128 bit integers are written in their decimal string form and JSON values serialized
+        Kind::String | Kind::U128 | Kind::I128 | Kind::JSON | Kind::Any => DataType::Utf8,
+        Kind::U64 => DataType::UInt64,
+        Kind::I64 => DataType::Int64,
+        Kind::U32 => DataType::UInt32,
+        Kind::I32 => DataType::Int32,
+        Kind::U16 => DataType::UInt16,
+        Kind::I16 => DataType::Int16,
+        Kind::U8 => DataType::UInt8,
+        Kind::I8 => DataType::Int8,
+        Kind::F64 => DataType::Float64,
+        Kind::F32 => DataType::Float32,
+        Kind::Bool => DataType::Boolean,
+    };
+    (data_type, false)
+}
+
+/// Build the Arrow fields for the entries of a struct
+fn fields<Metadata: Default + ArrowMetadata>(children: &[Entry<Metadata>]) -> Fields {
+    children.iter()
+        .filter(|child| !child.skip_serializing)
+        .map(|child| {
+            let (data_type, nullable) = data_type(&child.type_info);
+            Field::new(child.label.as_ref(), data_type, nullable).with_metadata(child.metadata.arrow_metadata())
+        })
+        .collect()
+}
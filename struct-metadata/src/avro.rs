@@ -0,0 +1,434 @@
+//! Generate Apache Avro schemas from descriptors and check schema resolution between them.
+//!
+//! Structs become records and enums become Avro enums, each named type is defined the first
+//! time it is used and referred to by name afterwards. Aliased types are written as the type
+//! they wrap. Avro has no unsigned or 128 bit integers, unsigned types up to 32 bits are widened
+//! to the next signed type, `u64` and 128 bit integers don't fit in a `long` and are written as
+//! strings. Field names, aliases and enum symbols are limited to letters, digits and
+//! underscores, other characters are replaced and labels that end up sharing a name are
+//! rejected.
+
+use core::fmt;
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{json, Map, Value};
+
+use crate::named::{child_path, field_name, unalias};
+use crate::{Compatibility, Descriptor, Entry, Kind};
+
+/// Reasons a descriptor can't be written as an Avro schema
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AvroError {
+    /// Two labels of a record or enum are written as the same Avro name
+    NameCollision {
+        /// Record or enum the labels belong to
+        name: String,
+        /// Label that claimed the name first
+        first: String,
+        /// Label that was written as the same name
+        second: String,
+        /// The shared Avro name
+        written: String,
+    },
+}
+
+impl fmt::Display for AvroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AvroError::NameCollision { name, first, second, written } => write!(f, "{name}: {first} and {second} are both written as {written}"),
+        }
+    }
+}
+
+impl std::error::Error for AvroError {}
+
+/// Configurable Avro schema generator
+#[derive(Debug, Clone, Default)]
+pub struct Avro {
+    /// Namespace set on the outermost record
+    namespace: Option<String>,
+    /// Known default values for the fields of the outermost record
+    defaults: Option<Value>,
+}
+
+impl Avro {
+    /// Create a generator with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the namespace of the outermost named type, nested types inherit it
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_owned());
+        self
+    }
+
+    /// Provide the default values for fields with a serde default.
+    ///
+    /// The value is usually a serialized `Default` instance of the described type, values are
+    /// looked up by field label and nested records use the matching nested object. Fields with a
+    /// default but no known value fall back to the zero value for their type.
+    pub fn defaults(mut self, values: Value) -> Self {
+        self.defaults = Some(values);
+        self
+    }
+
+    /// Build the schema for a type
+    pub fn schema<Metadata: Default>(&self, descriptor: &Descriptor<Metadata>) -> Result<Value, AvroError> {
+        let mut defined = BTreeSet::new();
+        let mut schema = avro_type(descriptor, self.defaults.as_ref(), &mut defined)?;
+        if let (Some(namespace), Value::Object(schema)) = (&self.namespace, &mut schema) {
+            schema.insert("namespace".to_owned(), json!(namespace));
+        }
+        Ok(schema)
+    }
+}
+
+impl<Metadata: Default> Descriptor<Metadata> {
+    /// Build an Avro schema for this type with the default generator settings
+    pub fn avro_schema(&self) -> Result<Value, AvroError> {
+        Avro::new().schema(self)
+    }
+}
+
+/// Build the schema for a value, `defaults` holds known field values when it is a record
+fn avro_type<'a, Metadata: Default>(descriptor: &'a Descriptor<Metadata>, defaults: Option<&Value>, defined: &mut BTreeSet<&'a str>) -> Result<Value, AvroError> {
+    Ok(match &descriptor.kind {
+        Kind::Struct { name, children } => {
+            if !defined.insert(name) {
+                return Ok(json!(name))
+            }
+            let mut record = Map::new();
+            record.insert("type".to_owned(), json!("record"));
+            record.insert("name".to_owned(), json!(name));
+            if let Some(docs) = &descriptor.docs {
+                record.insert("doc".to_owned(), json!(docs.join("\n")));
+            }
+            let mut written = Written::new();
+            let mut fields = vec![];
+            for child in children.iter().filter(|child| !(child.skip_serializing && child.skip_deserializing)) {
+                fields.push(field(name, child, defaults.and_then(|values| values.get(child.label.as_ref())), defined, &mut written)?);
+            }
+            record.insert("fields".to_owned(), Value::Array(fields));
+            Value::Object(record)
+        },
+        Kind::Enum { name, variants } => {
+            if !defined.insert(name) {
+                return Ok(json!(name))
+            }
+            let mut schema = Map::new();
+            schema.insert("type".to_owned(), json!("enum"));
+            schema.insert("name".to_owned(), json!(name));
+            if let Some(docs) = &descriptor.docs {
+                schema.insert("doc".to_owned(), json!(docs.join("\n")));
+            }
+            let mut written = Written::new();
+            let mut symbols = vec![];
+            for variant in variants {
+                symbols.push(claim(name, &variant.label, field_name(&variant.label, '_'), &mut written)?);
+            }
+            schema.insert("symbols".to_owned(), json!(symbols));
+            Value::Object(schema)
+        },
+        Kind::Sequence(kind) => json!({"type": "array", "items": avro_type(kind, None, defined)?}),
+        Kind::Mapping(_, value) => json!({"type": "map", "values": avro_type(value, None, defined)?}),
+        Kind::Option(kind) => {
+            let mut branches = vec![json!("null")];
+            match avro_type(kind, defaults, defined)? {
+                // unions can't be nested, and may only contain null once
+                Value::Array(inner) => branches.extend(inner.into_iter().filter(|branch| branch != "null")),
+                inner => branches.push(inner),
+            }
+            Value::Array(branches)
+        },
+        Kind::DateTime => json!({"type": "long", "logicalType": "timestamp-micros"}),
+        Kind::String | Kind::U128 | Kind::I128 | Kind::U64 => json!("string"),
+        Kind::I64 | Kind::U32 => json!("long"),
+        Kind::I32 | Kind::U16 | Kind::I16 | Kind::U8 | Kind::I8 => json!("int"),
+        Kind::F64 => json!("double"),
+        Kind::F32 => json!("float"),
+        Kind::Bool => json!("boolean"),
+        // arbitrary values are carried as serialized JSON
+        Kind::JSON | Kind::Any => json!("string"),
+        Kind::Aliased { kind, .. } => avro_type(kind, defaults, defined)?,
+    })
+}
+
+/// Names already written within a record or enum, with the label each was written for
+type Written<'a> = BTreeMap<String, &'a str>;
+
+/// Reserve the Avro name a label is written as, failing if another label already uses it
+fn claim<'a>(name: &str, label: &'a str, written: String, names: &mut Written<'a>) -> Result<String, AvroError> {
+    match names.get(&written) {
+        Some(first) if *first != label => Err(AvroError::NameCollision { name: name.to_owned(), first: first.to_string(), second: label.to_owned(), written }),
+        _ => {
+            names.insert(written.clone(), label);
+            Ok(written)
+        }
+    }
+}
+
+/// Build the schema for a record field
+fn field<'a, Metadata: Default>(record: &str, entry: &'a Entry<Metadata>, known: Option<&Value>, defined: &mut BTreeSet<&'a str>, names: &mut Written<'a>) -> Result<Value, AvroError> {
+    let mut output = Map::new();
+    let name = claim(record, &entry.label, field_name(&entry.label, '_'), names)?;
+    let mut aliases = vec![];
+    for alias in &entry.aliases {
+        let alias = claim(record, &entry.label, field_name(alias, '_'), names)?;
+        if alias != name && !aliases.contains(&alias) {
+            aliases.push(alias);
+        }
+    }
+    output.insert("name".to_owned(), json!(name));
+    let mut schema = avro_type(&entry.type_info, known, defined)?;
+
+    let optional = matches!(unalias(&entry.type_info).kind, Kind::Option(_));
+    let default = if entry.has_default || entry.skip_deserializing || optional {
+        known.map(|value| match (written_as_string(&entry.type_info), value) {
+            (true, Value::Number(number)) => json!(number.to_string()),
+            (_, value) => value.clone(),
+        }).or_else(|| zero_value(&entry.type_info))
+    } else {
+        None
+    };
+
+    // the default of a union has to match its first branch
+    if let (Some(default), Value::Array(branches)) = (&default, &mut schema) {
+        if !default.is_null() && branches.len() == 2 {
+            branches.swap(0, 1);
+        }
+    }
+
+    output.insert("type".to_owned(), schema);
+    if let Some(docs) = &entry.docs {
+        output.insert("doc".to_owned(), json!(docs.join("\n")));
+    }
+    if !aliases.is_empty() {
+        output.insert("aliases".to_owned(), json!(aliases));
+    }
+    if let Some(default) = default {
+        output.insert("default".to_owned(), default);
+    }
+    Ok(Value::Object(output))
+}
+
+/// Is a value an integer too large for a `long`, which is written as a string
+fn written_as_string<Metadata: Default>(descriptor: &Descriptor<Metadata>) -> bool {
+    match &unalias(descriptor).kind {
+        Kind::Option(kind) => written_as_string(kind),
+        kind => matches!(kind, Kind::U64 | Kind::U128 | Kind::I128),
+    }
+}
+
+/// The value `Default::default()` produces for a type, when it can be known
+fn zero_value<Metadata: Default>(descriptor: &Descriptor<Metadata>) -> Option<Value> {
+    Some(match &unalias(descriptor).kind {
+        Kind::Option(_) => Value::Null,
+        Kind::Sequence(_) => json!([]),
+        Kind::Mapping(..) => json!({}),
+        Kind::String => json!(""),
+        Kind::U128 | Kind::I128 | Kind::U64 => json!("0"),
+        Kind::DateTime | Kind::I64 | Kind::U32 | Kind::I32 | Kind::U16 | Kind::I16 | Kind::U8 | Kind::I8 => json!(0),
+        Kind::F64 | Kind::F32 => json!(0.0),
+        Kind::Bool => json!(false),
+        _ => return None,
+    })
+}
+
+/// A place where data written with one schema can't be read with another
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ResolutionError {
+    /// Dotted path to the field, `[]` marks array items and `*` map values
+    pub path: String,
+    /// Why the data can't be resolved
+    pub message: String,
+}
+
+impl fmt::Display for ResolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "<root>: {}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Stand in for missing arrays in a schema
+static EMPTY: Vec<Value> = Vec::new();
+
+/// Named types defined in a schema
+type Names<'a> = BTreeMap<&'a str, &'a Value>;
+
+/// Check whether data written with the `writer` schema can be read with the `reader` schema
+/// following the Avro schema resolution rules.
+pub fn resolution_errors(writer: &Value, reader: &Value) -> Vec<ResolutionError> {
+    let mut resolver = Resolver {
+        writer_names: Names::new(),
+        reader_names: Names::new(),
+        visiting: BTreeSet::new(),
+    };
+    collect_names(writer, &mut resolver.writer_names);
+    collect_names(reader, &mut resolver.reader_names);
+    let mut output = vec![];
+    resolver.resolve(writer, reader, "", &mut output);
+    output
+}
+
+/// Classify the compatibility between two versions of a schema under Avro schema resolution
+pub fn compatibility(old: &Value, new: &Value) -> Compatibility {
+    Compatibility::from_flags(resolution_errors(old, new).is_empty(), resolution_errors(new, old).is_empty())
+}
+
+/// Find every named type defined within a schema
+fn collect_names<'a>(schema: &'a Value, names: &mut Names<'a>) {
+    match schema {
+        Value::Array(branches) => branches.iter().for_each(|branch| collect_names(branch, names)),
+        Value::Object(object) => {
+            if let Some(name) = object.get("name").and_then(Value::as_str) {
+                names.insert(short_name(name), schema);
+            }
+            for key in ["items", "values"] {
+                if let Some(inner) = object.get(key) {
+                    collect_names(inner, names);
+                }
+            }
+            if let Some(Value::Array(fields)) = object.get("fields") {
+                for field in fields {
+                    if let Some(inner) = field.get("type") {
+                        collect_names(inner, names);
+                    }
+                }
+            }
+        },
+        _ => {}
+    }
+}
+
+/// Name of a type without its namespace
+fn short_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// State used while resolving one schema against another
+struct Resolver<'a> {
+    /// Named types of the writer schema
+    writer_names: Names<'a>,
+    /// Named types of the reader schema
+    reader_names: Names<'a>,
+    /// Pairs of records currently being compared, to stop at recursive types
+    visiting: BTreeSet<(&'a str, &'a str)>,
+}
+
+impl<'a> Resolver<'a> {
+    /// Replace a reference to a named type with its definition
+    fn lookup(names: &Names<'a>, schema: &'a Value) -> &'a Value {
+        match schema.as_str().and_then(|name| names.get(short_name(name))) {
+            Some(definition) => definition,
+            None => schema,
+        }
+    }
+
+    /// Name of the kind of schema, ignoring logical types
+    fn type_name(schema: &Value) -> &str {
+        match schema {
+            Value::String(name) => name,
+            Value::Array(_) => "union",
+            Value::Object(object) => object.get("type").and_then(Value::as_str).unwrap_or("unknown"),
+            _ => "unknown",
+        }
+    }
+
+    /// Compare a writer schema to a reader schema, recording every problem found
+    fn resolve(&mut self, writer: &'a Value, reader: &'a Value, path: &str, output: &mut Vec<ResolutionError>) {
+        let writer = Self::lookup(&self.writer_names, writer);
+        let reader = Self::lookup(&self.reader_names, reader);
+
+        if let Value::Array(branches) = writer {
+            for branch in branches {
+                self.resolve(branch, reader, path, output);
+            }
+            return
+        }
+        if let Value::Array(branches) = reader {
+            for branch in branches {
+                let mut errors = vec![];
+                self.resolve(writer, branch, path, &mut errors);
+                if errors.is_empty() {
+                    return
+                }
+            }
+            output.push(ResolutionError { path: path.to_owned(), message: format!("{} doesn't match any branch of the reader union", Self::type_name(writer)) });
+            return
+        }
+
+        let writer_type = Self::type_name(writer);
+        let reader_type = Self::type_name(reader);
+        if writer_type != reader_type {
+            let promoted = matches!((writer_type, reader_type),
+                ("int", "long" | "float" | "double") | ("long", "float" | "double") | ("float", "double") | ("string", "bytes") | ("bytes", "string"));
+            if !promoted {
+                output.push(ResolutionError { path: path.to_owned(), message: format!("{writer_type} can't be read as {reader_type}") });
+            }
+            return
+        }
+
+        match writer_type {
+            "record" => {
+                let writer_name = writer["name"].as_str().map(short_name).unwrap_or_default();
+                let reader_name = reader["name"].as_str().map(short_name).unwrap_or_default();
+                if !Self::names_match(writer_name, reader) {
+                    output.push(ResolutionError { path: path.to_owned(), message: format!("record {writer_name} can't be read as {reader_name}") });
+                    return
+                }
+                if !self.visiting.insert((writer_name, reader_name)) {
+                    return
+                }
+                let writer_fields = writer["fields"].as_array().unwrap_or(&EMPTY);
+                for field in reader["fields"].as_array().unwrap_or(&EMPTY) {
+                    let name = field["name"].as_str().unwrap_or_default();
+                    let aliases = field.get("aliases").and_then(Value::as_array).unwrap_or(&EMPTY);
+                    let source = writer_fields.iter().find(|candidate| {
+                        let candidate = &candidate["name"];
+                        candidate == name || aliases.contains(candidate)
+                    });
+                    match source {
+                        Some(source) => self.resolve(&source["type"], &field["type"], &child_path(path, name), output),
+                        None => if field.get("default").is_none() {
+                            output.push(ResolutionError { path: child_path(path, name), message: "missing from written data and has no default".to_owned() });
+                        },
+                    }
+                }
+                self.visiting.remove(&(writer_name, reader_name));
+            },
+            "enum" => {
+                let writer_name = writer["name"].as_str().map(short_name).unwrap_or_default();
+                if !Self::names_match(writer_name, reader) {
+                    output.push(ResolutionError { path: path.to_owned(), message: format!("enum {writer_name} can't be read as {}", reader["name"]) });
+                    return
+                }
+                if reader.get("default").is_some() {
+                    return
+                }
+                let symbols = reader["symbols"].as_array().unwrap_or(&EMPTY);
+                for symbol in writer["symbols"].as_array().unwrap_or(&EMPTY) {
+                    if !symbols.contains(symbol) {
+                        output.push(ResolutionError { path: path.to_owned(), message: format!("symbol {symbol} is unknown to the reader") });
+                    }
+                }
+            },
+            "array" => self.resolve(&writer["items"], &reader["items"], &format!("{path}[]"), output),
+            "map" => self.resolve(&writer["values"], &reader["values"], &child_path(path, "*"), output),
+            _ => {}
+        }
+    }
+
+    /// Does a reader named type accept data written under a name, directly or through aliases
+    fn names_match(writer_name: &str, reader: &Value) -> bool {
+        if reader["name"].as_str().map(short_name) == Some(writer_name) {
+            return true
+        }
+        reader.get("aliases").and_then(Value::as_array).into_iter().flatten()
+            .any(|alias| alias.as_str().map(short_name) == Some(writer_name))
+    }
+}
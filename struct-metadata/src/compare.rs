@@ -0,0 +1,301 @@
+//! Path level comparison of descriptors for use in tests.
+//!
+//! Unlike `==`, which prints two complete Debug trees when used with `assert_eq!`, a comparison
+//! lists each difference with the path it was found at. Which parts of a descriptor are compared
+//! can be configured, aliases in particular are ignored by the `PartialEq` implementation of
+//! [`Entry`] but compared here unless turned off.
+
+use core::fmt;
+use std::borrow::Cow;
+
+use crate::{Descriptor, Entry, Kind, PrettyMetadata, Variant};
+
+/// Settings controlling what is compared
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CompareOptions {
+    /// Compare doc strings
+    pub docs: bool,
+    /// Compare the aliases of fields and variants
+    pub aliases: bool,
+    /// Compare metadata of types, fields and variants
+    pub metadata: bool,
+    /// Stop after this many differences have been found, 0 reports every difference
+    pub limit: usize,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self { docs: true, aliases: true, metadata: true, limit: 10 }
+    }
+}
+
+impl CompareOptions {
+    /// Leave doc strings out of the comparison
+    pub fn ignore_docs(mut self) -> Self {
+        self.docs = false;
+        self
+    }
+
+    /// Leave aliases out of the comparison
+    pub fn ignore_aliases(mut self) -> Self {
+        self.aliases = false;
+        self
+    }
+
+    /// Leave metadata out of the comparison
+    pub fn ignore_metadata(mut self) -> Self {
+        self.metadata = false;
+        self
+    }
+
+    /// Set the number of differences reported, 0 removes the limit
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+}
+
+/// A difference found at a particular location in a type
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Difference {
+    /// Dotted path to the differing item, `[]` marks sequence items, `{}` mapping keys and `*`
+    /// mapping values
+    pub path: String,
+    /// Rendering of the expected value
+    pub expected: String,
+    /// Rendering of the actual value
+    pub actual: String,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "<root>" } else { &self.path };
+        write!(f, "{path}: {} vs {}", self.expected, self.actual)
+    }
+}
+
+impl<Metadata: Default + PartialEq + PrettyMetadata> Descriptor<Metadata> {
+    /// Compare this expected descriptor against an actual one with the default options
+    pub fn compare(&self, actual: &Self) -> Vec<Difference> {
+        self.compare_with(actual, CompareOptions::default())
+    }
+
+    /// Compare this expected descriptor against an actual one, returning up to
+    /// `options.limit` differences
+    pub fn compare_with(&self, actual: &Self, options: CompareOptions) -> Vec<Difference> {
+        let mut comparison = Comparison { options, output: vec![] };
+        comparison.descriptor(&mut vec![], self, actual);
+        comparison.output
+    }
+}
+
+/// Check that two descriptors are equal, panicking with the path of each difference if not.
+///
+/// Takes the expected and actual descriptors, followed optionally by [`CompareOptions`].
+#[macro_export]
+macro_rules! assert_descriptor_eq {
+    ($expected:expr, $actual:expr) => {
+        $crate::assert_descriptor_eq!($expected, $actual, $crate::CompareOptions::default())
+    };
+    ($expected:expr, $actual:expr, $options:expr) => {{
+        let options: $crate::CompareOptions = $options;
+        // look for one difference past the limit to tell if any were left out
+        let search = match options.limit {
+            0 => options,
+            limit => options.limit(limit.saturating_add(1)),
+        };
+        let differences = $expected.compare_with(&$actual, search);
+        if !differences.is_empty() {
+            let mut message = String::from("descriptors are not equal (expected vs actual):");
+            let shown = if options.limit == 0 { differences.len() } else { differences.len().min(options.limit) };
+            for difference in &differences[..shown] {
+                message.push_str("\n    ");
+                message.push_str(&difference.to_string());
+            }
+            if shown < differences.len() {
+                message.push_str("\n    ...");
+            }
+            panic!("{}", message);
+        }
+    }};
+}
+
+/// State of a comparison in progress
+struct Comparison {
+    /// What is being compared
+    options: CompareOptions,
+    /// Differences found so far
+    output: Vec<Difference>,
+}
+
+impl Comparison {
+    /// Has the difference limit been reached
+    fn full(&self) -> bool {
+        self.options.limit != 0 && self.output.len() >= self.options.limit
+    }
+
+    /// Record a difference
+    fn push(&mut self, path: &[&str], aspect: &str, expected: impl fmt::Display, actual: impl fmt::Display) {
+        if self.full() {
+            return
+        }
+        let mut path = path.join(".");
+        if !aspect.is_empty() {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(aspect);
+        }
+        self.output.push(Difference { path, expected: expected.to_string(), actual: actual.to_string() });
+    }
+
+    /// Compare the docs, metadata and kind of a type
+    fn descriptor<'a, Metadata: Default + PartialEq + PrettyMetadata>(&mut self, path: &mut Vec<&'a str>, expected: &'a Descriptor<Metadata>, actual: &'a Descriptor<Metadata>) {
+        self.docs(path, &expected.docs, &actual.docs);
+        self.metadata(path, &expected.metadata, &actual.metadata);
+        self.kind(path, &expected.kind, &actual.kind);
+    }
+
+    /// Compare doc strings
+    fn docs(&mut self, path: &[&str], expected: &Option<Vec<Cow<'static, str>>>, actual: &Option<Vec<Cow<'static, str>>>) {
+        if self.options.docs && expected != actual {
+            self.push(path, "docs", format!("{expected:?}"), format!("{actual:?}"));
+        }
+    }
+
+    /// Compare metadata
+    fn metadata<Metadata: PartialEq + PrettyMetadata>(&mut self, path: &[&str], expected: &Metadata, actual: &Metadata) {
+        if self.options.metadata && expected != actual {
+            self.push(path, "metadata", expected.pretty(), actual.pretty());
+        }
+    }
+
+    /// Compare aliases, ignoring the label the derive macro includes in them
+    fn aliases(&mut self, path: &[&str], label: &str, expected: &[Cow<'static, str>], actual: &[Cow<'static, str>]) {
+        let expected: Vec<_> = expected.iter().filter(|alias| **alias != label).collect();
+        let actual: Vec<_> = actual.iter().filter(|alias| **alias != label).collect();
+        if self.options.aliases && expected != actual {
+            self.push(path, "aliases", format!("{expected:?}"), format!("{actual:?}"));
+        }
+    }
+
+    /// Compare the shape of two types
+    fn kind<'a, Metadata: Default + PartialEq + PrettyMetadata>(&mut self, path: &mut Vec<&'a str>, expected: &'a Kind<Metadata>, actual: &'a Kind<Metadata>) {
+        if self.full() {
+            return
+        }
+        match (expected, actual) {
+            (Kind::Struct { name: expected_name, children: expected }, Kind::Struct { name: actual_name, children: actual }) => {
+                if expected_name != actual_name {
+                    self.push(path, "name", expected_name, actual_name);
+                }
+                self.fields(path, expected, actual);
+            },
+            (Kind::Enum { name: expected_name, variants: expected }, Kind::Enum { name: actual_name, variants: actual }) => {
+                if expected_name != actual_name {
+                    self.push(path, "name", expected_name, actual_name);
+                }
+                self.variants(path, expected, actual);
+            },
+            (Kind::Aliased { name: expected_name, kind: expected }, Kind::Aliased { name: actual_name, kind: actual }) => {
+                if expected_name != actual_name {
+                    self.push(path, "name", expected_name, actual_name);
+                }
+                self.descriptor(path, expected, actual);
+            },
+            (Kind::Option(expected), Kind::Option(actual)) => self.descriptor(path, expected, actual),
+            (Kind::Sequence(expected), Kind::Sequence(actual)) => {
+                path.push("[]");
+                self.descriptor(path, expected, actual);
+                path.pop();
+            },
+            (Kind::Mapping(expected_key, expected_value), Kind::Mapping(actual_key, actual_value)) => {
+                path.push("{}");
+                self.descriptor(path, expected_key, actual_key);
+                path.pop();
+                path.push("*");
+                self.descriptor(path, expected_value, actual_value);
+                path.pop();
+            },
+            (expected, actual) => {
+                let (expected, actual) = (kind_label(expected), kind_label(actual));
+                if expected != actual {
+                    self.push(path, "kind", expected, actual);
+                }
+            },
+        }
+    }
+
+    /// Compare the fields of two structs, matching them by label
+    fn fields<'a, Metadata: Default + PartialEq + PrettyMetadata>(&mut self, path: &mut Vec<&'a str>, expected: &'a [Entry<Metadata>], actual: &'a [Entry<Metadata>]) {
+        for entry in expected {
+            let Some(other) = actual.iter().find(|other| other.label == entry.label) else {
+                self.push(path, "fields", &entry.label, "(missing)");
+                continue
+            };
+            path.push(&entry.label);
+            self.docs(path, &entry.docs, &other.docs);
+            self.metadata(path, &entry.metadata, &other.metadata);
+            self.aliases(path, &entry.label, &entry.aliases, &other.aliases);
+            for (aspect, expected, actual) in [
+                ("default", entry.has_default, other.has_default),
+                ("skip_serializing", entry.skip_serializing, other.skip_serializing),
+                ("skip_deserializing", entry.skip_deserializing, other.skip_deserializing),
+            ] {
+                if expected != actual {
+                    self.push(path, aspect, expected, actual);
+                }
+            }
+            self.descriptor(path, &entry.type_info, &other.type_info);
+            path.pop();
+        }
+        for entry in actual {
+            if !expected.iter().any(|other| other.label == entry.label) {
+                self.push(path, "fields", "(missing)", &entry.label);
+            }
+        }
+        self.order(path, "fields", expected.iter().map(|entry| entry.label.as_ref()), actual.iter().map(|entry| entry.label.as_ref()));
+    }
+
+    /// Compare the variants of two enums, matching them by label
+    fn variants<'a, Metadata: Default + PartialEq + PrettyMetadata>(&mut self, path: &mut Vec<&'a str>, expected: &'a [Variant<Metadata>], actual: &'a [Variant<Metadata>]) {
+        for variant in expected {
+            let Some(other) = actual.iter().find(|other| other.label == variant.label) else {
+                self.push(path, "variants", &variant.label, "(missing)");
+                continue
+            };
+            path.push(&variant.label);
+            self.docs(path, &variant.docs, &other.docs);
+            self.metadata(path, &variant.metadata, &other.metadata);
+            self.aliases(path, &variant.label, &variant.aliases, &other.aliases);
+            path.pop();
+        }
+        for variant in actual {
+            if !expected.iter().any(|other| other.label == variant.label) {
+                self.push(path, "variants", "(missing)", &variant.label);
+            }
+        }
+        self.order(path, "variants", expected.iter().map(|variant| variant.label.as_ref()), actual.iter().map(|variant| variant.label.as_ref()));
+    }
+
+    /// Compare the order of the labels present on both sides
+    fn order<'a>(&mut self, path: &[&str], aspect: &str, expected: impl Iterator<Item = &'a str>, actual: impl Iterator<Item = &'a str>) {
+        let expected: Vec<_> = expected.collect();
+        let actual: Vec<_> = actual.collect();
+        let expected_order: Vec<_> = expected.iter().filter(|label| actual.contains(label)).collect();
+        let actual_order: Vec<_> = actual.iter().filter(|label| expected_order.contains(label)).collect();
+        if expected_order != actual_order {
+            self.push(path, aspect, format!("{expected_order:?}"), format!("{actual_order:?}"));
+        }
+    }
+}
+
+/// Short description of a kind used when two kinds differ
+fn kind_label<Metadata: Default>(kind: &Kind<Metadata>) -> String {
+    match kind {
+        Kind::Struct { name, .. } => format!("struct {name}"),
+        Kind::Enum { name, .. } => format!("enum {name}"),
+        Kind::Aliased { name, .. } => format!("alias {name}"),
+        kind => kind.type_expression(),
+    }
+}
@@ -0,0 +1,307 @@
+//! Export diagrams of the relationships between types as Mermaid or Graphviz DOT.
+//!
+//! Structs, enums and aliased types become nodes listing their fields or variants. Every field
+//! that refers to another named type becomes an edge labeled with the field and the containers
+//! the type is reached through.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+
+use crate::{Descriptor, Kind};
+
+/// How a field reaches the type an edge points at
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Relationship {
+    /// Through an optional value
+    Option,
+    /// Through the items of a list
+    Sequence,
+    /// Through the keys of a map
+    MappingKey,
+    /// Through the values of a map
+    MappingValue,
+    /// Through an aliased type
+    Aliased,
+}
+
+impl Relationship {
+    /// Name used in edge labels
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Relationship::Option => "Option",
+            Relationship::Sequence => "Sequence",
+            Relationship::MappingKey => "Mapping key",
+            Relationship::MappingValue => "Mapping value",
+            Relationship::Aliased => "Aliased",
+        }
+    }
+}
+
+/// A reference from one named type to another
+struct Edge<'a> {
+    /// Type holding the reference
+    from: &'a str,
+    /// Type being referred to
+    to: &'a str,
+    /// Field holding the reference, empty for aliased types
+    field: &'a str,
+    /// Containers the referred type is reached through
+    path: Vec<Relationship>,
+}
+
+impl Edge<'_> {
+    /// Text describing the edge
+    fn label(&self) -> String {
+        let path: Vec<_> = self.path.iter().map(Relationship::as_str).collect();
+        match (self.field.is_empty(), path.is_empty()) {
+            (true, _) => path.join(", "),
+            (false, true) => self.field.to_owned(),
+            (false, false) => format!("{} ({})", self.field, path.join(", ")),
+        }
+    }
+
+    /// Is the referred type optional or repeated
+    fn many(&self) -> bool {
+        self.path.iter().any(|relationship| matches!(relationship, Relationship::Sequence | Relationship::MappingKey | Relationship::MappingValue))
+    }
+}
+
+/// Named types and the references between them
+struct Graph<'a, Metadata: Default> {
+    /// Included types by name
+    nodes: BTreeMap<&'a str, &'a Descriptor<Metadata>>,
+    /// References between included types
+    edges: Vec<Edge<'a>>,
+}
+
+/// Configurable diagram exporter
+#[derive(Debug, Clone, Default)]
+pub struct Diagram {
+    /// Number of references to follow from the roots
+    max_depth: Option<usize>,
+    /// Leave out enums and aliased types that don't refer to other types
+    collapse_leaves: bool,
+}
+
+impl Diagram {
+    /// Create an exporter with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only include types reachable within the given number of references from the roots
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Leave out enums and aliased types that don't refer to other types, they are still
+    /// shown as the type of the fields using them
+    pub fn collapse_leaves(mut self, collapse: bool) -> Self {
+        self.collapse_leaves = collapse;
+        self
+    }
+
+    /// Build a Mermaid `classDiagram`
+    pub fn mermaid_class<'a, Metadata: Default + 'a>(&self, roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> String {
+        let graph = self.graph(roots);
+        let mut output = String::from("classDiagram\n");
+        for (name, descriptor) in &graph.nodes {
+            let _ = writeln!(output, "    class {name} {{");
+            match &descriptor.kind {
+                Kind::Struct { children, .. } => {
+                    for child in children {
+                        let _ = writeln!(output, "        +{} {}", mermaid_generics(&child.type_info.kind.type_expression()), child.label);
+                    }
+                },
+                Kind::Enum { variants, .. } => {
+                    output.push_str("        <<enumeration>>\n");
+                    for variant in variants {
+                        let _ = writeln!(output, "        {}", variant.label);
+                    }
+                },
+                Kind::Aliased { kind, .. } => {
+                    let _ = writeln!(output, "        <<alias>>\n        {}", mermaid_generics(&kind.kind.type_expression()));
+                },
+                _ => {}
+            }
+            output.push_str("    }\n");
+        }
+        for edge in &graph.edges {
+            let cardinality = if edge.many() { "\"*\" " } else { "" };
+            let _ = writeln!(output, "    {} --> {cardinality}{} : {}", edge.from, edge.to, edge.label());
+        }
+        output
+    }
+
+    /// Build a Mermaid `erDiagram`
+    pub fn mermaid_er<'a, Metadata: Default + 'a>(&self, roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> String {
+        let graph = self.graph(roots);
+        let mut output = String::from("erDiagram\n");
+        for (name, descriptor) in &graph.nodes {
+            let _ = writeln!(output, "    {name} {{");
+            match &descriptor.kind {
+                Kind::Struct { children, .. } => {
+                    for child in children {
+                        let _ = writeln!(output, "        {} {}", er_type(&child.type_info.kind.type_expression()), er_type(&child.label));
+                    }
+                },
+                Kind::Enum { variants, .. } => {
+                    for variant in variants {
+                        let _ = writeln!(output, "        variant {}", er_type(&variant.label));
+                    }
+                },
+                Kind::Aliased { kind, .. } => {
+                    let _ = writeln!(output, "        {} value", er_type(&kind.kind.type_expression()));
+                },
+                _ => {}
+            }
+            output.push_str("    }\n");
+        }
+        for edge in &graph.edges {
+            let right = match (edge.many(), edge.path.contains(&Relationship::Option)) {
+                (true, _) => "o{",
+                (false, true) => "o|",
+                (false, false) => "||",
+            };
+            let _ = writeln!(output, "    {} ||--{right} {} : \"{}\"", edge.from, edge.to, edge.label().replace('"', "'"));
+        }
+        output
+    }
+
+    /// Build a Graphviz DOT graph
+    pub fn dot<'a, Metadata: Default + 'a>(&self, roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> String {
+        let graph = self.graph(roots);
+        let mut output = String::from("digraph types {\n    node [shape=record];\n");
+        for (name, descriptor) in &graph.nodes {
+            let members: Vec<String> = match &descriptor.kind {
+                Kind::Struct { children, .. } => children.iter().map(|child| format!("{}: {}", child.label, child.type_info.kind.type_expression())).collect(),
+                Kind::Enum { variants, .. } => variants.iter().map(|variant| variant.label.to_string()).collect(),
+                Kind::Aliased { kind, .. } => vec![kind.kind.type_expression()],
+                _ => vec![],
+            };
+            let mut label = record_escape(name);
+            if matches!(descriptor.kind, Kind::Enum { .. }) {
+                label += "\\n(enumeration)";
+            }
+            label += "|";
+            for member in members {
+                label += &record_escape(&member);
+                label += "\\l";
+            }
+            let _ = writeln!(output, "    {} [label=\"{{{label}}}\"];", dot_id(name));
+        }
+        for edge in &graph.edges {
+            let _ = writeln!(output, "    {} -> {} [label=\"{}\"];", dot_id(edge.from), dot_id(edge.to), edge.label().replace('"', "\\\""));
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    /// Collect the types and references to include
+    fn graph<'a, Metadata: Default + 'a>(&self, roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> Graph<'a, Metadata> {
+        let mut depths: BTreeMap<&'a str, (usize, &'a Descriptor<Metadata>)> = BTreeMap::new();
+        let mut queue = VecDeque::new();
+        let mut candidates = vec![];
+
+        for root in roots {
+            references(root, "", "", &mut vec![], &mut candidates);
+            for (_, descriptor) in candidates.drain(..) {
+                queue.push_back((descriptor, 0));
+            }
+        }
+
+        let mut edges = vec![];
+        while let Some((descriptor, depth)) = queue.pop_front() {
+            let Some(name) = type_name(descriptor) else { continue };
+            if depths.contains_key(name) || self.max_depth.is_some_and(|max| depth > max) {
+                continue
+            }
+            depths.insert(name, (depth, descriptor));
+
+            let mut found = vec![];
+            match &descriptor.kind {
+                Kind::Struct { children, .. } => {
+                    for child in children {
+                        references(&child.type_info, name, &child.label, &mut vec![], &mut found);
+                    }
+                },
+                Kind::Aliased { kind, .. } => references(kind, name, "", &mut vec![Relationship::Aliased], &mut found),
+                _ => {}
+            }
+            for (edge, target) in found {
+                edges.push(edge);
+                queue.push_back((target, depth + 1));
+            }
+        }
+
+        let mut nodes: BTreeMap<&'a str, &'a Descriptor<Metadata>> = depths.into_iter().map(|(name, (_, descriptor))| (name, descriptor)).collect();
+        if self.collapse_leaves {
+            let leaves: Vec<&'a str> = nodes.iter()
+                .filter(|(name, descriptor)| !matches!(descriptor.kind, Kind::Struct { .. }) && !edges.iter().any(|edge| edge.from == **name))
+                .map(|(name, _)| *name)
+                .collect();
+            for leaf in leaves {
+                nodes.remove(leaf);
+            }
+        }
+        edges.retain(|edge| nodes.contains_key(edge.from) && nodes.contains_key(edge.to));
+        Graph { nodes, edges }
+    }
+}
+
+/// Name of a struct, enum or aliased type
+fn type_name<Metadata: Default>(descriptor: &Descriptor<Metadata>) -> Option<&str> {
+    match &descriptor.kind {
+        Kind::Struct { name, .. } | Kind::Enum { name, .. } | Kind::Aliased { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+/// Find the named types a value refers to along with the containers they are reached through
+fn references<'a, Metadata: Default>(descriptor: &'a Descriptor<Metadata>, from: &'a str, field: &'a str, path: &mut Vec<Relationship>, output: &mut Vec<(Edge<'a>, &'a Descriptor<Metadata>)>) {
+    let nested = |relationship, kind: &'a Descriptor<Metadata>, path: &mut Vec<Relationship>, output: &mut Vec<(Edge<'a>, &'a Descriptor<Metadata>)>| {
+        path.push(relationship);
+        references(kind, from, field, path, output);
+        path.pop();
+    };
+    match &descriptor.kind {
+        Kind::Struct { name, .. } | Kind::Enum { name, .. } | Kind::Aliased { name, .. } => {
+            output.push((Edge { from, to: name, field, path: path.clone() }, descriptor));
+        },
+        Kind::Option(kind) => nested(Relationship::Option, kind, path, output),
+        Kind::Sequence(kind) => nested(Relationship::Sequence, kind, path, output),
+        Kind::Mapping(key, value) => {
+            nested(Relationship::MappingKey, key, path, output);
+            nested(Relationship::MappingValue, value, path, output);
+        },
+        _ => {}
+    }
+}
+
+/// Mermaid class diagrams write generics with tildes
+fn mermaid_generics(expression: &str) -> String {
+    expression.replace(['<', '>'], "~")
+}
+
+/// Attribute types and names in entity relationship diagrams can't contain spaces or commas
+fn er_type(expression: &str) -> String {
+    expression.replace('<', "[").replace('>', "]").replace(", ", "_").replace(|c: char| !(c.is_ascii_alphanumeric() || "_-[]()".contains(c)), "_")
+}
+
+/// Quote a node identifier
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\\\""))
+}
+
+/// Escape the characters with special meaning in record labels
+fn record_escape(text: &str) -> String {
+    let mut output = String::new();
+    for c in text.chars() {
+        if "{}|<>\"".contains(c) {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+    output
+}
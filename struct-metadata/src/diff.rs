@@ -0,0 +1,373 @@
+//! Structural diff and compatibility checking between two versions of a type.
+//!
+//! Compatibility is classified the way schema registries do it. A change is backward compatible
+//! when data written with the old type can be read with the new one, and forward compatible when
+//! data written with the new type can be read with the old one. Unknown fields are assumed to be
+//! ignored by readers.
+
+use core::fmt;
+
+use crate::named::unalias;
+use crate::{Descriptor, Entry, Kind, PrettyMetadata};
+
+/// Compatibility of a change, or a set of changes, between two versions of a type
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Compatibility {
+    /// Old and new data can be read by either version
+    Full,
+    /// New readers can read old data
+    Backward,
+    /// Old readers can read new data
+    Forward,
+    /// Neither version can reliably read data from the other
+    None,
+}
+
+impl Compatibility {
+    /// Build from flags indicating each direction of compatibility
+    pub(crate) fn from_flags(backward: bool, forward: bool) -> Self {
+        match (backward, forward) {
+            (true, true) => Compatibility::Full,
+            (true, false) => Compatibility::Backward,
+            (false, true) => Compatibility::Forward,
+            (false, false) => Compatibility::None,
+        }
+    }
+
+    /// Can data written by the old version be read by the new one
+    pub fn is_backward(&self) -> bool {
+        matches!(self, Compatibility::Full | Compatibility::Backward)
+    }
+
+    /// Can data written by the new version be read by the old one
+    pub fn is_forward(&self) -> bool {
+        matches!(self, Compatibility::Full | Compatibility::Forward)
+    }
+
+    /// The compatibility left when both of two changes are applied
+    pub fn and(self, other: Self) -> Self {
+        Self::from_flags(self.is_backward() && other.is_backward(), self.is_forward() && other.is_forward())
+    }
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Compatibility::Full => "full",
+            Compatibility::Backward => "backward",
+            Compatibility::Forward => "forward",
+            Compatibility::None => "none",
+        })
+    }
+}
+
+/// Description of a single difference between two versions of a type
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ChangeKind {
+    /// A field was added
+    FieldAdded {
+        /// Whether the field may be missing from data (an option or a field with a default)
+        optional: bool,
+    },
+    /// A field was removed
+    FieldRemoved {
+        /// Whether the field could be missing from data in the old version
+        optional: bool,
+    },
+    /// A field was renamed, detected through the aliases of either version
+    FieldRenamed {
+        /// Label in the old version
+        from: String,
+        /// Label in the new version
+        to: String,
+    },
+    /// The type of a value changed
+    TypeChanged {
+        /// Type expression in the old version
+        from: String,
+        /// Type expression in the new version
+        to: String,
+    },
+    /// A required field became optional
+    BecameOptional,
+    /// An optional field became required
+    BecameRequired,
+    /// An enum variant was added
+    VariantAdded {
+        /// Label of the new variant
+        label: String,
+    },
+    /// An enum variant was removed
+    VariantRemoved {
+        /// Label of the removed variant
+        label: String,
+    },
+    /// The metadata attached to a type, field, or variant changed
+    MetadataChanged {
+        /// Rendering of the old metadata, see [`PrettyMetadata`]
+        from: String,
+        /// Rendering of the new metadata
+        to: String,
+    },
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::FieldAdded { optional: true } => write!(f, "added optional field"),
+            ChangeKind::FieldAdded { optional: false } => write!(f, "added required field"),
+            ChangeKind::FieldRemoved { optional: true } => write!(f, "removed optional field"),
+            ChangeKind::FieldRemoved { optional: false } => write!(f, "removed required field"),
+            ChangeKind::FieldRenamed { from, to } => write!(f, "renamed field from {from} to {to}"),
+            ChangeKind::TypeChanged { from, to } => write!(f, "changed type from {from} to {to}"),
+            ChangeKind::BecameOptional => write!(f, "became optional"),
+            ChangeKind::BecameRequired => write!(f, "became required"),
+            ChangeKind::VariantAdded { label } => write!(f, "added variant {label}"),
+            ChangeKind::VariantRemoved { label } => write!(f, "removed variant {label}"),
+            ChangeKind::MetadataChanged { from, to } => write!(f, "changed metadata from {from} to {to}"),
+        }
+    }
+}
+
+/// A difference found at a particular location in a type
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Change {
+    /// Dotted path to the changed item, `[]` marks sequence items and `*` mapping values
+    pub path: String,
+    /// What changed
+    pub kind: ChangeKind,
+    /// How this change affects reading data between versions
+    pub compatibility: Compatibility,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "<root>" } else { &self.path };
+        write!(f, "{path}: {} ({})", self.kind, self.compatibility)
+    }
+}
+
+/// All of the differences between two versions of a type
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SchemaDiff {
+    /// Changes in the order they were found
+    pub changes: Vec<Change>,
+}
+
+impl SchemaDiff {
+    /// Were no differences found
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Overall compatibility between the two versions
+    pub fn compatibility(&self) -> Compatibility {
+        self.changes.iter().fold(Compatibility::Full, |acc, change| acc.and(change.compatibility))
+    }
+
+    /// Record a change
+    fn push(&mut self, path: &[&str], kind: ChangeKind, backward: bool, forward: bool) {
+        self.changes.push(Change {
+            path: path.join("."),
+            kind,
+            compatibility: Compatibility::from_flags(backward, forward),
+        })
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "compatibility: {}", self.compatibility())?;
+        for change in &self.changes {
+            writeln!(f, "  - {change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<Metadata: Default + PartialEq + PrettyMetadata> Descriptor<Metadata> {
+    /// Compare this (old) version of a type against a new one
+    pub fn diff(&self, new: &Self) -> SchemaDiff {
+        let mut output = SchemaDiff::default();
+        diff_kinds(&mut output, &mut vec![], self, new);
+        output
+    }
+}
+
+/// Signedness and width of integer types, used to detect widening
+fn numeric_rank<Metadata: Default>(kind: &Kind<Metadata>) -> Option<(bool, u32)> {
+    // (signed, bits)
+    Some(match kind {
+        Kind::U8 => (false, 8),
+        Kind::U16 => (false, 16),
+        Kind::U32 => (false, 32),
+        Kind::U64 => (false, 64),
+        Kind::U128 => (false, 128),
+        Kind::I8 => (true, 8),
+        Kind::I16 => (true, 16),
+        Kind::I32 => (true, 32),
+        Kind::I64 => (true, 64),
+        Kind::I128 => (true, 128),
+        _ => return None,
+    })
+}
+
+/// Can every value of the old type be represented by the new type
+fn widens<Metadata: Default>(old: &Kind<Metadata>, new: &Kind<Metadata>) -> bool {
+    match (old, new) {
+        (Kind::F32, Kind::F64) => true,
+        (old, Kind::F64) => matches!(numeric_rank(old), Some((_, bits)) if bits <= 32),
+        (old, Kind::F32) => matches!(numeric_rank(old), Some((_, bits)) if bits <= 16),
+        _ => match (numeric_rank(old), numeric_rank(new)) {
+            (Some((false, old_bits)), Some((false, new_bits))) |
+            (Some((true, old_bits)), Some((true, new_bits))) => old_bits < new_bits,
+            (Some((false, old_bits)), Some((true, new_bits))) => old_bits < new_bits,
+            _ => false,
+        }
+    }
+}
+
+/// Record a change to the metadata at a location
+fn diff_metadata<Metadata: PartialEq + PrettyMetadata>(output: &mut SchemaDiff, path: &[&str], old: &Metadata, new: &Metadata) {
+    if old != new {
+        output.push(path, ChangeKind::MetadataChanged { from: old.pretty(), to: new.pretty() }, true, true);
+    }
+}
+
+/// Compare the type of two values at the same location
+fn diff_kinds<'a, Metadata: Default + PartialEq + PrettyMetadata>(output: &mut SchemaDiff, path: &mut Vec<&'a str>, old: &'a Descriptor<Metadata>, new: &'a Descriptor<Metadata>) {
+    // When only one side is optional the metadata is compared against the wrapped type instead
+    if matches!(old.kind, Kind::Option(_)) == matches!(new.kind, Kind::Option(_)) {
+        diff_metadata(output, path, &old.metadata, &new.metadata);
+    }
+    match (&old.kind, &new.kind) {
+        (Kind::Aliased { kind: old_inner, .. }, Kind::Aliased { kind: new_inner, .. }) => diff_kinds(output, path, old_inner, new_inner),
+        (Kind::Aliased { kind: old_inner, .. }, _) => diff_kinds(output, path, old_inner, new),
+        (_, Kind::Aliased { kind: new_inner, .. }) => diff_kinds(output, path, old, new_inner),
+        (Kind::Struct { children: old_children, .. }, Kind::Struct { children: new_children, .. }) => {
+            diff_fields(output, path, old_children, new_children);
+        },
+        (Kind::Enum { variants: old_variants, .. }, Kind::Enum { variants: new_variants, .. }) => {
+            for variant in old_variants {
+                match new_variants.iter().find(|new| new.label == variant.label || new.aliases.contains(&variant.label)) {
+                    Some(new) => {
+                        path.push(&new.label);
+                        diff_metadata(output, path, &variant.metadata, &new.metadata);
+                        path.pop();
+                    },
+                    None => output.push(path, ChangeKind::VariantRemoved { label: variant.label.to_string() }, false, true),
+                }
+            }
+            for variant in new_variants {
+                if !old_variants.iter().any(|old| variant.label == old.label || variant.aliases.contains(&old.label)) {
+                    output.push(path, ChangeKind::VariantAdded { label: variant.label.to_string() }, true, false);
+                }
+            }
+        },
+        (Kind::Option(old_inner), Kind::Option(new_inner)) => diff_kinds(output, path, old_inner, new_inner),
+        (Kind::Option(old_inner), _) => {
+            output.push(path, ChangeKind::BecameRequired, false, true);
+            diff_kinds(output, path, old_inner, new);
+        },
+        (_, Kind::Option(new_inner)) => {
+            output.push(path, ChangeKind::BecameOptional, true, false);
+            diff_kinds(output, path, old, new_inner);
+        },
+        (Kind::Sequence(old_inner), Kind::Sequence(new_inner)) => {
+            path.push("[]");
+            diff_kinds(output, path, old_inner, new_inner);
+            path.pop();
+        },
+        (Kind::Mapping(old_key, old_value), Kind::Mapping(new_key, new_value)) => {
+            diff_kinds(output, path, old_key, new_key);
+            path.push("*");
+            diff_kinds(output, path, old_value, new_value);
+            path.pop();
+        },
+        (old_kind, new_kind) => {
+            let from = old_kind.type_expression();
+            let to = new_kind.type_expression();
+            if from != to {
+                let backward = widens(old_kind, new_kind);
+                let forward = widens(new_kind, old_kind);
+                output.push(path, ChangeKind::TypeChanged { from, to }, backward, forward);
+            }
+        }
+    }
+}
+
+/// Can this field be absent from data
+fn is_optional<Metadata: Default>(entry: &Entry<Metadata>) -> bool {
+    entry.has_default || matches!(unalias(&entry.type_info).kind, Kind::Option(_))
+}
+
+/// Compare the fields of two structs, matching renamed fields through their aliases
+fn diff_fields<'a, Metadata: Default + PartialEq + PrettyMetadata>(output: &mut SchemaDiff, path: &mut Vec<&'a str>, old: &'a [Entry<Metadata>], new: &'a [Entry<Metadata>]) {
+    let mut matched = vec![false; new.len()];
+
+    // Resolve every exact label match before considering aliases, so an alias can't claim a
+    // field that is still present under its own label
+    let mut pairs: Vec<Option<usize>> = old.iter().map(|old_entry| {
+        let index = new.iter().position(|new_entry| new_entry.label == old_entry.label)?;
+        matched[index] = true;
+        Some(index)
+    }).collect();
+    for (old_entry, pair) in old.iter().zip(pairs.iter_mut()) {
+        if pair.is_none() {
+            *pair = new.iter().enumerate().position(|(index, new_entry)| {
+                !matched[index] && (new_entry.aliases.contains(&old_entry.label) || old_entry.aliases.contains(&new_entry.label))
+            });
+            if let Some(index) = *pair {
+                matched[index] = true;
+            }
+        }
+    }
+
+    for (old_entry, found) in old.iter().zip(pairs) {
+        let Some(index) = found else {
+            path.push(&old_entry.label);
+            let optional = is_optional(old_entry);
+            output.push(path, ChangeKind::FieldRemoved { optional }, true, optional);
+            path.pop();
+            continue
+        };
+        let new_entry = &new[index];
+
+        path.push(&new_entry.label);
+        if new_entry.label != old_entry.label {
+            let backward = new_entry.aliases.contains(&old_entry.label);
+            let forward = old_entry.aliases.contains(&new_entry.label);
+            output.push(path, ChangeKind::FieldRenamed { from: old_entry.label.to_string(), to: new_entry.label.to_string() }, backward, forward);
+        }
+
+        // Defaults make a field optional without changing the type, report them separately
+        let old_wrapped = matches!(unalias(&old_entry.type_info).kind, Kind::Option(_));
+        let new_wrapped = matches!(unalias(&new_entry.type_info).kind, Kind::Option(_));
+        if old_wrapped == new_wrapped {
+            if old_entry.has_default && !new_entry.has_default && !new_wrapped {
+                output.push(path, ChangeKind::BecameRequired, false, true);
+            } else if !old_entry.has_default && new_entry.has_default && !new_wrapped {
+                output.push(path, ChangeKind::BecameOptional, true, false);
+            }
+        }
+
+        diff_metadata(output, path, &old_entry.metadata, &new_entry.metadata);
+
+        // Dropping the Option from a field with a default leaves it just as optional
+        let old_type = match &unalias(&old_entry.type_info).kind {
+            Kind::Option(inner) if new_entry.has_default && !new_wrapped => inner,
+            _ => &old_entry.type_info,
+        };
+        diff_kinds(output, path, old_type, &new_entry.type_info);
+        path.pop();
+    }
+
+    for (new_entry, matched) in new.iter().zip(matched) {
+        if !matched {
+            path.push(&new_entry.label);
+            let optional = is_optional(new_entry);
+            output.push(path, ChangeKind::FieldAdded { optional }, optional, true);
+            path.pop();
+        }
+    }
+}
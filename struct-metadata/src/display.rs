@@ -0,0 +1,199 @@
+//! Human readable rendering of descriptors as an indented tree.
+
+use core::fmt;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::{Descriptor, Kind};
+
+/// Rendering of metadata in the tree printed by [`Pretty`]
+pub trait PrettyMetadata: fmt::Debug {
+    /// Text written after the type or field the metadata belongs to
+    fn pretty(&self) -> String { format!("{self:?}") }
+}
+
+/// Entries are sorted so the output doesn't depend on hash order
+impl<K: fmt::Debug, V: fmt::Debug> PrettyMetadata for HashMap<K, V> {
+    fn pretty(&self) -> String {
+        let mut entries: Vec<String> = self.iter().map(|(key, value)| format!("{key:?}: {value:?}")).collect();
+        entries.sort();
+        format!("{{{}}}", entries.join(", "))
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> PrettyMetadata for Vec<(K, V)> {}
+
+impl<Metadata: Default> Kind<Metadata> {
+    /// Render this type as a rust-like type expression such as `Option<Vec<String>>`
+    pub fn type_expression(&self) -> String {
+        match self {
+            Kind::Struct { name, .. } |
+            Kind::Aliased { name, .. } |
+            Kind::Enum { name, .. } => name.to_string(),
+            Kind::Sequence(kind) => format!("Vec<{}>", kind.kind.type_expression()),
+            Kind::Option(kind) => format!("Option<{}>", kind.kind.type_expression()),
+            Kind::Mapping(key, value) => format!("HashMap<{}, {}>", key.kind.type_expression(), value.kind.type_expression()),
+            Kind::DateTime => "DateTime".to_owned(),
+            Kind::String => "String".to_owned(),
+            Kind::U128 => "u128".to_owned(),
+            Kind::I128 => "i128".to_owned(),
+            Kind::U64 => "u64".to_owned(),
+            Kind::I64 => "i64".to_owned(),
+            Kind::U32 => "u32".to_owned(),
+            Kind::I32 => "i32".to_owned(),
+            Kind::U16 => "u16".to_owned(),
+            Kind::I16 => "i16".to_owned(),
+            Kind::U8 => "u8".to_owned(),
+            Kind::I8 => "i8".to_owned(),
+            Kind::F64 => "f64".to_owned(),
+            Kind::F32 => "f32".to_owned(),
+            Kind::Bool => "bool".to_owned(),
+            Kind::JSON => "Value".to_owned(),
+            Kind::Any => "Any".to_owned(),
+        }
+    }
+}
+
+impl<Metadata: Default> Descriptor<Metadata> {
+    /// Get a pretty printer for this descriptor that can be configured before display
+    pub fn pretty(&self) -> Pretty<'_, Metadata> {
+        Pretty {
+            descriptor: self,
+            max_depth: None,
+            docs: true,
+            metadata: true,
+        }
+    }
+
+    /// Find the struct or enum that should be expanded below a field of this type
+    fn expandable(&self) -> Option<&Self> {
+        match &self.kind {
+            Kind::Struct { .. } | Kind::Enum { .. } => Some(self),
+            Kind::Aliased { kind, .. } |
+            Kind::Sequence(kind) |
+            Kind::Option(kind) |
+            Kind::Mapping(_, kind) => kind.expandable(),
+            _ => None,
+        }
+    }
+}
+
+/// Configurable tree renderer for a descriptor, built with [`Descriptor::pretty`]
+#[derive(Debug, Clone, Copy)]
+pub struct Pretty<'a, Metadata: Default> {
+    /// Descriptor being rendered
+    descriptor: &'a Descriptor<Metadata>,
+    /// How many levels of nested types should be expanded
+    max_depth: Option<usize>,
+    /// Should doc strings be included
+    docs: bool,
+    /// Should non-default metadata be included
+    metadata: bool,
+}
+
+impl<Metadata: Default> Pretty<'_, Metadata> {
+    /// Limit how many levels of nested fields are expanded
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Leave doc strings out of the output
+    pub fn hide_docs(mut self) -> Self {
+        self.docs = false;
+        self
+    }
+
+    /// Leave metadata out of the output
+    pub fn hide_metadata(mut self) -> Self {
+        self.metadata = false;
+        self
+    }
+}
+
+impl<Metadata: Default + PartialEq + PrettyMetadata> Pretty<'_, Metadata> {
+    /// Write doc strings at the given indentation
+    fn write_docs(&self, f: &mut fmt::Formatter<'_>, indent: usize, docs: &Option<Vec<Cow<'static, str>>>) -> fmt::Result {
+        if !self.docs {
+            return Ok(())
+        }
+        for line in docs.iter().flatten() {
+            if line.is_empty() {
+                writeln!(f, "{:indent$}///", "")?;
+            } else {
+                writeln!(f, "{:indent$}/// {line}", "")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write metadata if it has been changed from the default
+    fn write_metadata(&self, f: &mut fmt::Formatter<'_>, metadata: &Metadata) -> fmt::Result {
+        if self.metadata && *metadata != Metadata::default() {
+            write!(f, " {}", metadata.pretty())?;
+        }
+        Ok(())
+    }
+
+    /// Write the fields or variants of a struct or enum
+    fn write_members(&self, f: &mut fmt::Formatter<'_>, descriptor: &Descriptor<Metadata>, depth: usize) -> fmt::Result {
+        if let Some(max_depth) = self.max_depth {
+            if depth > max_depth {
+                return Ok(())
+            }
+        }
+        let indent = depth * 4;
+
+        match &descriptor.kind {
+            Kind::Struct { children, .. } => {
+                for child in children {
+                    self.write_docs(f, indent, &child.docs)?;
+                    write!(f, "{:indent$}{}: {}", "", child.label, child.type_info.kind.type_expression())?;
+                    if child.has_default {
+                        write!(f, " = default")?;
+                    }
+                    if child.aliases.iter().any(|alias| *alias != child.label) {
+                        write!(f, " (aliases: {})", child.aliases.join(", "))?;
+                    }
+                    self.write_metadata(f, &child.metadata)?;
+                    writeln!(f)?;
+                    if let Some(inner) = child.type_info.expandable() {
+                        self.write_members(f, inner, depth + 1)?;
+                    }
+                }
+            },
+            Kind::Enum { variants, .. } => {
+                for variant in variants {
+                    self.write_docs(f, indent, &variant.docs)?;
+                    write!(f, "{:indent$}{}", "", variant.label)?;
+                    if variant.aliases.iter().any(|alias| *alias != variant.label) {
+                        write!(f, " (aliases: {})", variant.aliases.join(", "))?;
+                    }
+                    self.write_metadata(f, &variant.metadata)?;
+                    writeln!(f)?;
+                }
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl<Metadata: Default + PartialEq + PrettyMetadata> fmt::Display for Pretty<'_, Metadata> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_docs(f, 0, &self.descriptor.docs)?;
+        write!(f, "{}", self.descriptor.kind.type_expression())?;
+        self.write_metadata(f, &self.descriptor.metadata)?;
+        writeln!(f)?;
+        if let Some(inner) = self.descriptor.expandable() {
+            self.write_members(f, inner, 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Metadata: Default + PartialEq + PrettyMetadata> fmt::Display for Descriptor<Metadata> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pretty().fmt(f)
+    }
+}
@@ -0,0 +1,310 @@
+//! Generate Elasticsearch/OpenSearch index mappings from descriptors.
+//!
+//! How each field is indexed is controlled through the [`ElasticMetadata`] trait, which the
+//! metadata type of the described struct implements.
+
+use core::fmt;
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::{json, Map, Value};
+
+use crate::named::unquote;
+use crate::{Descriptor, Entry, Kind};
+
+/// Metadata settings that control how a field is indexed
+pub trait ElasticMetadata {
+    /// Should the field be searchable, sets the `index` mapping parameter
+    fn index(&self) -> Option<bool> { None }
+    /// Should the field value be stored separately, sets the `store` mapping parameter
+    fn store(&self) -> Option<bool> { None }
+    /// Other fields this value should be copied into
+    fn copy_to(&self) -> Vec<String> { vec![] }
+    /// Analyzer used for text fields, setting an analyzer makes a string field `text`
+    fn analyzer(&self) -> Option<String> { None }
+    /// Override the field type chosen for this field (`text`, `object`, `ip`, etc.)
+    fn mapping_type(&self) -> Option<String> { None }
+}
+
+/// Reads the `index`, `store`, `copy_to` (comma separated), `analyzer` and `mapping` keys
+impl<K: AsRef<str>, V: AsRef<str>> ElasticMetadata for HashMap<K, V> {
+    fn index(&self) -> Option<bool> {
+        self.iter().find(|(key, _)| key.as_ref() == "index").map(|(_, value)| unquote(value.as_ref()) == "true")
+    }
+
+    fn store(&self) -> Option<bool> {
+        self.iter().find(|(key, _)| key.as_ref() == "store").map(|(_, value)| unquote(value.as_ref()) == "true")
+    }
+
+    fn copy_to(&self) -> Vec<String> {
+        match self.iter().find(|(key, _)| key.as_ref() == "copy_to") {
+            Some((_, value)) => unquote(value.as_ref()).split(',').map(|name| name.trim().to_owned()).filter(|name| !name.is_empty()).collect(),
+            None => vec![],
+        }
+    }
+
+    fn analyzer(&self) -> Option<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "analyzer").map(|(_, value)| unquote(value.as_ref()).to_owned())
+    }
+
+    fn mapping_type(&self) -> Option<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "mapping").map(|(_, value)| unquote(value.as_ref()).to_owned())
+    }
+}
+
+/// Generator for index bodies
+#[derive(Debug, Clone)]
+pub struct Elastic {
+    /// Value of the `dynamic` setting on the root of the mapping
+    dynamic: String,
+}
+
+impl Default for Elastic {
+    fn default() -> Self {
+        Self { dynamic: "strict".to_owned() }
+    }
+}
+
+impl Elastic {
+    /// Create a generator with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the `dynamic` setting for the root of the mapping (`strict` by default)
+    pub fn dynamic(mut self, dynamic: &str) -> Self {
+        self.dynamic = dynamic.to_owned();
+        self
+    }
+
+    /// Build the mappings section for a struct
+    pub fn mappings<Metadata: Default + ElasticMetadata>(&self, descriptor: &Descriptor<Metadata>) -> Value {
+        let mut templates = vec![];
+        let mut path = vec![];
+        let properties = properties(&descriptor.kind, &mut path, &mut templates);
+        let mut mappings = json!({"dynamic": self.dynamic, "properties": properties});
+        if !templates.is_empty() {
+            mappings["dynamic_templates"] = Value::Array(templates);
+        }
+        mappings
+    }
+
+    /// Build a complete index body for a struct, ready to be used when creating an index
+    pub fn index_body<Metadata: Default + ElasticMetadata>(&self, descriptor: &Descriptor<Metadata>) -> Value {
+        json!({"mappings": self.mappings(descriptor)})
+    }
+}
+
+/// Build the properties object for the fields of a struct, other kinds produce no properties
+fn properties<'a, Metadata: Default + ElasticMetadata>(kind: &'a Kind<Metadata>, path: &mut Vec<&'a str>, templates: &mut Vec<Value>) -> Map<String, Value> {
+    let mut output = Map::new();
+    match kind {
+        Kind::Struct { children, .. } => {
+            for child in children {
+                path.push(&child.label);
+                output.insert(child.label.to_string(), field(child, path, templates));
+                path.pop();
+            }
+        },
+        Kind::Aliased { kind, .. } |
+        Kind::Option(kind) |
+        Kind::Sequence(kind) => return properties(&kind.kind, path, templates),
+        _ => {}
+    }
+    output
+}
+
+/// Build the mapping for a single field
+fn field<'a, Metadata: Default + ElasticMetadata>(entry: &'a Entry<Metadata>, path: &mut Vec<&'a str>, templates: &mut Vec<Value>) -> Value {
+    let mut mapping = value_mapping(&entry.type_info, &entry.metadata, path, templates);
+    field_parameters(&mut mapping, &entry.metadata);
+    mapping
+}
+
+/// Add the parameters that apply to any leaf field type, object and nested fields reject them
+fn field_parameters<Metadata: ElasticMetadata>(mapping: &mut Value, metadata: &Metadata) {
+    if matches!(mapping["type"].as_str(), Some("object" | "nested")) {
+        return
+    }
+    if let Some(index) = metadata.index() {
+        mapping["index"] = json!(index);
+    }
+    if let Some(store) = metadata.store() {
+        mapping["store"] = json!(store);
+    }
+    let copy_to = metadata.copy_to();
+    if !copy_to.is_empty() {
+        mapping["copy_to"] = json!(copy_to);
+    }
+}
+
+/// Name of the field type used for a scalar kind
+fn scalar_type<Metadata: Default>(kind: &Kind<Metadata>) -> &'static str {
+    match kind {
+        Kind::DateTime => "date",
+        Kind::U64 => "unsigned_long",
+        Kind::I64 | Kind::U32 => "long",
+        Kind::I32 | Kind::U16 => "integer",
+        Kind::I16 | Kind::U8 => "short",
+        Kind::I8 => "byte",
+        Kind::F64 => "double",
+        Kind::F32 => "float",
+        Kind::Bool => "boolean",
+        Kind::JSON | Kind::Any => "flattened",
+        // 128 bit integers don't fit any numeric field type
+        _ => "keyword",
+    }
+}
+
+/// Build the mapping for a value given the metadata of the field holding it
+fn value_mapping<'a, Metadata: Default + ElasticMetadata>(descriptor: &'a Descriptor<Metadata>, metadata: &Metadata, path: &mut Vec<&'a str>, templates: &mut Vec<Value>) -> Value {
+    let override_type = metadata.mapping_type();
+    match &descriptor.kind {
+        Kind::Aliased { kind, .. } |
+        Kind::Option(kind) => value_mapping(kind, metadata, path, templates),
+        Kind::Sequence(kind) => {
+            let mut mapping = value_mapping(kind, metadata, path, templates);
+            // Arrays of objects are nested so that the values of each item stay together
+            if override_type.is_none() && mapping["type"] == "object" && mapping.get("properties").is_some() {
+                mapping["type"] = json!("nested");
+            }
+            mapping
+        },
+        Kind::Struct { .. } => {
+            json!({"type": override_type.unwrap_or_else(|| "object".to_owned()), "properties": properties(&descriptor.kind, path, templates)})
+        },
+        Kind::Mapping(_, value) => {
+            path.push("*");
+            dynamic_templates(value, metadata, path, templates);
+            path.pop();
+            json!({"type": "object", "dynamic": true})
+        },
+        Kind::String => {
+            let analyzer = metadata.analyzer();
+            let mut mapping = json!({"type": override_type.unwrap_or_else(|| if analyzer.is_some() { "text" } else { "keyword" }.to_owned())});
+            if let Some(analyzer) = analyzer {
+                mapping["analyzer"] = json!(analyzer);
+            }
+            mapping
+        },
+        Kind::Enum { .. } => json!({"type": override_type.unwrap_or_else(|| "keyword".to_owned())}),
+        kind => json!({"type": override_type.unwrap_or_else(|| scalar_type(kind).to_owned())}),
+    }
+}
+
+/// Add dynamic templates covering every leaf below the values of a mapping
+fn dynamic_templates<'a, Metadata: Default + ElasticMetadata>(descriptor: &'a Descriptor<Metadata>, metadata: &Metadata, path: &mut Vec<&'a str>, templates: &mut Vec<Value>) {
+    match &descriptor.kind {
+        Kind::Aliased { kind, .. } |
+        Kind::Option(kind) |
+        Kind::Sequence(kind) => dynamic_templates(kind, metadata, path, templates),
+        Kind::Struct { children, .. } => {
+            for child in children {
+                path.push(&child.label);
+                dynamic_templates(&child.type_info, &child.metadata, path, templates);
+                path.pop();
+            }
+        },
+        _ => {
+            let mut nested = vec![];
+            let mut mapping = value_mapping(descriptor, metadata, path, &mut nested);
+            field_parameters(&mut mapping, metadata);
+            let name = path.join(".");
+            let mut template = Map::new();
+            template.insert(name.clone(), json!({"path_match": name, "mapping": mapping}));
+            templates.push(Value::Object(template));
+            templates.extend(nested);
+        }
+    }
+}
+
+/// A difference between a generated mapping and an existing one
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MappingDifference {
+    /// A field in the generated mapping is missing from the existing one
+    Missing {
+        /// Dotted path of the field
+        path: String,
+    },
+    /// The existing mapping has a field that wasn't generated
+    Unexpected {
+        /// Dotted path of the field
+        path: String,
+    },
+    /// A mapping parameter has a different value
+    Changed {
+        /// Dotted path of the field
+        path: String,
+        /// Name of the parameter
+        parameter: String,
+        /// Generated value
+        expected: Value,
+        /// Value in the existing mapping, `null` if missing
+        actual: Value,
+    },
+}
+
+impl fmt::Display for MappingDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingDifference::Missing { path } => write!(f, "{path}: missing from existing mapping"),
+            MappingDifference::Unexpected { path } => write!(f, "{path}: not in generated mapping"),
+            MappingDifference::Changed { path, parameter, expected, actual } => write!(f, "{path}: {parameter} expected {expected} found {actual}"),
+        }
+    }
+}
+
+/// Compare a generated mapping to an existing one.
+///
+/// Either an index body (with a `mappings` key) or a bare mappings object may be given.
+pub fn compare_mappings(generated: &Value, existing: &Value) -> Vec<MappingDifference> {
+    let generated = generated.get("mappings").unwrap_or(generated);
+    let existing = existing.get("mappings").unwrap_or(existing);
+    let mut output = vec![];
+    compare_field(&mut output, "", generated, existing);
+    output
+}
+
+/// Compare a generated mapping to one stored in a JSON file
+pub fn compare_mapping_file(generated: &Value, path: impl AsRef<Path>) -> std::io::Result<Vec<MappingDifference>> {
+    let existing: Value = serde_json::from_slice(&std::fs::read(path)?)?;
+    Ok(compare_mappings(generated, &existing))
+}
+
+/// Compare the parameters and properties of a single field mapping
+fn compare_field(output: &mut Vec<MappingDifference>, path: &str, generated: &Value, existing: &Value) {
+    let empty = Map::new();
+    let generated_map = generated.as_object().unwrap_or(&empty);
+    let existing_map = existing.as_object().unwrap_or(&empty);
+
+    for (parameter, expected) in generated_map {
+        if parameter == "properties" {
+            continue
+        }
+        let actual = existing_map.get(parameter).cloned().unwrap_or(Value::Null);
+        if *expected != actual {
+            output.push(MappingDifference::Changed {
+                path: if path.is_empty() { "<root>".to_owned() } else { path.to_owned() },
+                parameter: parameter.clone(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+    }
+
+    let generated_properties = generated_map.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+    let existing_properties = existing_map.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+    for (name, generated) in generated_properties {
+        let child = if path.is_empty() { name.clone() } else { format!("{path}.{name}") };
+        match existing_properties.get(name) {
+            Some(existing) => compare_field(output, &child, generated, existing),
+            None => output.push(MappingDifference::Missing { path: child }),
+        }
+    }
+    for name in existing_properties.keys() {
+        if !generated_properties.contains_key(name) {
+            let child = if path.is_empty() { name.clone() } else { format!("{path}.{name}") };
+            output.push(MappingDifference::Unexpected { path: child });
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Stable structural hashing of descriptors.
+//!
+//! The hash is computed with 64 bit FNV-1a over a fixed little-endian encoding so that the
+//! same type produces the same fingerprint on every platform and compiler version.
+
+use std::collections::HashMap;
+
+use crate::{Descriptor, Kind};
+
+/// Metadata support needed to fingerprint a descriptor
+pub trait FingerprintMetadata {
+    /// Key value pairs describing this metadata, included when metadata is fingerprinted
+    fn fingerprint_pairs(&self) -> Vec<(String, String)> { vec![] }
+    /// Fields with volatile metadata are left out of fingerprints
+    fn is_volatile(&self) -> bool { false }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> FingerprintMetadata for HashMap<K, V> {
+    fn fingerprint_pairs(&self) -> Vec<(String, String)> {
+        let mut pairs: Vec<_> = self.iter().map(|(key, value)| (key.as_ref().to_owned(), value.as_ref().to_owned())).collect();
+        pairs.sort();
+        pairs
+    }
+
+    fn is_volatile(&self) -> bool {
+        self.iter().any(|(key, value)| key.as_ref() == "volatile" && value.as_ref() == "true")
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> FingerprintMetadata for Vec<(K, V)> {
+    fn fingerprint_pairs(&self) -> Vec<(String, String)> {
+        let mut pairs: Vec<_> = self.iter().map(|(key, value)| (key.as_ref().to_owned(), value.as_ref().to_owned())).collect();
+        pairs.sort();
+        pairs
+    }
+
+    fn is_volatile(&self) -> bool {
+        self.iter().any(|(key, value)| key.as_ref() == "volatile" && value.as_ref() == "true")
+    }
+}
+
+/// Settings controlling what is included in a fingerprint
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FingerprintOptions {
+    /// Include metadata of types, fields and variants
+    pub metadata: bool,
+    /// Leave out fields whose metadata is marked as volatile
+    pub skip_volatile: bool,
+}
+
+impl Default for FingerprintOptions {
+    fn default() -> Self {
+        Self { metadata: false, skip_volatile: true }
+    }
+}
+
+/// Incremental 64 bit FNV-1a hasher
+struct Fnv(u64);
+
+impl Fnv {
+    /// FNV offset basis
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    /// FNV prime
+    const PRIME: u64 = 0x100000001b3;
+
+    /// Add raw bytes
+    fn bytes(&mut self, data: &[u8]) {
+        for byte in data {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Add a length value in a platform independent way
+    fn length(&mut self, value: usize) {
+        self.bytes(&(value as u64).to_le_bytes());
+    }
+
+    /// Add a length prefixed string
+    fn string(&mut self, value: &str) {
+        self.length(value.len());
+        self.bytes(value.as_bytes());
+    }
+}
+
+impl<Metadata: Default + FingerprintMetadata> Descriptor<Metadata> {
+    /// Hash the structure of this type using the default options.
+    ///
+    /// Field labels, variant labels, kinds, nesting and defaults are included. Doc strings,
+    /// metadata and the names of structs and enums are not, since they don't change the shape
+    /// of the data. Fields marked volatile are skipped.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint_with(FingerprintOptions::default())
+    }
+
+    /// Hash the structure of this type with control over what is included
+    pub fn fingerprint_with(&self, options: FingerprintOptions) -> u64 {
+        let mut hasher = Fnv(Fnv::OFFSET);
+        self.fingerprint_into(&mut hasher, options);
+        hasher.0
+    }
+
+    /// Recursive helper for `fingerprint_with`
+    fn fingerprint_into(&self, hasher: &mut Fnv, options: FingerprintOptions) {
+        if options.metadata {
+            fingerprint_metadata(hasher, &self.metadata);
+        }
+        match &self.kind {
+            Kind::Struct { .. } => hasher.string("struct"),
+            Kind::Aliased { .. } => hasher.string("aliased"),
+            Kind::Enum { .. } => hasher.string("enum"),
+            Kind::Sequence(_) => hasher.string("sequence"),
+            Kind::Option(_) => hasher.string("option"),
+            Kind::Mapping(..) => hasher.string("mapping"),
+            kind => hasher.string(&kind.type_expression()),
+        }
+
+        match &self.kind {
+            Kind::Struct { children, .. } => {
+                let children: Vec<_> = children.iter().filter(|child| !(options.skip_volatile && child.metadata.is_volatile())).collect();
+                hasher.length(children.len());
+                for child in children {
+                    hasher.string(&child.label);
+                    hasher.bytes(&[child.has_default as u8]);
+                    if options.metadata {
+                        fingerprint_metadata(hasher, &child.metadata);
+                    }
+                    child.type_info.fingerprint_into(hasher, options);
+                }
+            },
+            Kind::Enum { variants, .. } => {
+                hasher.length(variants.len());
+                for variant in variants {
+                    hasher.string(&variant.label);
+                    if options.metadata {
+                        fingerprint_metadata(hasher, &variant.metadata);
+                    }
+                }
+            },
+            Kind::Aliased { kind, .. } |
+            Kind::Sequence(kind) |
+            Kind::Option(kind) => kind.fingerprint_into(hasher, options),
+            Kind::Mapping(key, value) => {
+                key.fingerprint_into(hasher, options);
+                value.fingerprint_into(hasher, options);
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Add the canonical pairs of a metadata value to a hash
+fn fingerprint_metadata<Metadata: FingerprintMetadata>(hasher: &mut Fnv, metadata: &Metadata) {
+    let pairs = metadata.fingerprint_pairs();
+    hasher.length(pairs.len());
+    for (key, value) in pairs {
+        hasher.string(&key);
+        hasher.string(&value);
+    }
+}
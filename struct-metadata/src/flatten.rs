@@ -0,0 +1,105 @@
+//! Flatten a descriptor into a list of leaf field paths.
+//!
+//! Search engines and columnar stores generally want each leaf field under a dotted path
+//! rather than a tree of nested types.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{Descriptor, Kind};
+
+/// Combining the metadata of enclosing fields into nested ones while flattening
+pub trait MergeMetadata {
+    /// Combine metadata from an enclosing field into this one, the default keeps this one as is
+    fn merge_outer(&mut self, _outer: &Self) {}
+}
+
+/// Keys missing from the inner map are copied from the outer one
+impl<K: Eq + Hash + Clone, V: Clone> MergeMetadata for HashMap<K, V> {
+    fn merge_outer(&mut self, outer: &Self) {
+        for (key, value) in outer {
+            self.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+/// Values missing from the inner list are appended from the outer one
+impl<V: PartialEq + Clone> MergeMetadata for Vec<V> {
+    fn merge_outer(&mut self, outer: &Self) {
+        for value in outer {
+            if !self.contains(value) {
+                self.push(value.clone());
+            }
+        }
+    }
+}
+
+/// How fields nested under a mapping should be handled while flattening
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MappingPolicy {
+    /// Continue into the mapping's value type using a `*` path segment for the key
+    #[default]
+    Wildcard,
+    /// Stop at the mapping and report it as a leaf field
+    Stop,
+}
+
+/// A single leaf field found while flattening a descriptor
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FlatField<'a, Metadata: Default> {
+    /// Dotted path of labels leading to this field
+    pub path: String,
+    /// Type of the leaf field
+    pub kind: &'a Kind<Metadata>,
+    /// Entry metadata merged from the outermost field down to this one
+    pub metadata: Metadata,
+    /// Whether any container along the path is a sequence
+    pub multivalued: bool,
+    /// Whether any container along the path is an option
+    pub optional: bool,
+}
+
+impl<Metadata: Default + MergeMetadata + Clone> Descriptor<Metadata> {
+    /// List every leaf field in this type in declaration order.
+    ///
+    /// Aliased types are treated as the type they wrap. Metadata is merged along each path
+    /// using [`MergeMetadata::merge_outer`] so that outer fields provide defaults for inner ones.
+    pub fn flatten(&self, mapping: MappingPolicy) -> Vec<FlatField<'_, Metadata>> {
+        let mut output = vec![];
+        let mut path = vec![];
+        self.flatten_into(&mut path, &Metadata::default(), false, false, mapping, &mut output);
+        output
+    }
+
+    /// Recursive helper for `flatten`
+    fn flatten_into<'a>(&'a self, path: &mut Vec<&'a str>, metadata: &Metadata, multivalued: bool, optional: bool, mapping: MappingPolicy, output: &mut Vec<FlatField<'a, Metadata>>) {
+        match &self.kind {
+            Kind::Struct { children, .. } => {
+                for child in children {
+                    let mut merged = child.metadata.clone();
+                    merged.merge_outer(metadata);
+                    path.push(&child.label);
+                    child.type_info.flatten_into(path, &merged, multivalued, optional, mapping, output);
+                    path.pop();
+                }
+            },
+            Kind::Aliased { kind, .. } => kind.flatten_into(path, metadata, multivalued, optional, mapping, output),
+            Kind::Sequence(kind) => kind.flatten_into(path, metadata, true, optional, mapping, output),
+            Kind::Option(kind) => kind.flatten_into(path, metadata, multivalued, true, mapping, output),
+            Kind::Mapping(_, value) if mapping == MappingPolicy::Wildcard => {
+                path.push("*");
+                value.flatten_into(path, metadata, multivalued, optional, mapping, output);
+                path.pop();
+            },
+            kind => {
+                output.push(FlatField {
+                    path: path.join("."),
+                    kind,
+                    metadata: metadata.clone(),
+                    multivalued,
+                    optional,
+                })
+            }
+        }
+    }
+}
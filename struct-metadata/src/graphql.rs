@@ -0,0 +1,232 @@
+//! Generate GraphQL schema definitions from descriptors.
+//!
+//! Structs become object types, and optionally input types named with an `Input` suffix, enums
+//! become GraphQL enums with their labels converted into upper case names. GraphQL has no type
+//! aliases or maps, aliased types are written as the type they wrap and maps, along with
+//! arbitrary JSON values, are written as a configurable scalar. GraphQL can't deprecate a type,
+//! so a deprecated type instead deprecates every field that refers to it.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::named::{constant_name, doc_lines, field_name, named_types, string_literal, unquote};
+use crate::{Descriptor, Entry, Kind};
+
+/// Metadata settings read by the GraphQL generator
+pub trait GraphQLMetadata {
+    /// Mark a field, enum value or type as deprecated, with an optional reason
+    fn deprecated(&self) -> Option<Option<String>> { None }
+}
+
+/// Reads the `deprecated` key, `true` deprecates without a reason and any other value is
+/// used as the reason
+impl<K: AsRef<str>, V: AsRef<str>> GraphQLMetadata for HashMap<K, V> {
+    fn deprecated(&self) -> Option<Option<String>> {
+        let (_, value) = self.iter().find(|(key, _)| key.as_ref() == "deprecated")?;
+        match unquote(value.as_ref()) {
+            "false" => None,
+            "true" => Some(None),
+            reason => Some(Some(reason.to_owned())),
+        }
+    }
+}
+
+/// Configurable GraphQL schema generator
+#[derive(Debug, Clone)]
+pub struct GraphQL {
+    /// Scalar used for maps and arbitrary JSON values
+    json_scalar: String,
+    /// Scalar used for integers that don't fit in a GraphQL `Int`
+    big_integer_scalar: String,
+    /// Scalar used for timestamps
+    datetime_scalar: String,
+    /// Should input types be written alongside object types
+    inputs: bool,
+}
+
+impl Default for GraphQL {
+    fn default() -> Self {
+        Self {
+            json_scalar: "JSON".to_owned(),
+            big_integer_scalar: "String".to_owned(),
+            datetime_scalar: "DateTime".to_owned(),
+            inputs: false,
+        }
+    }
+}
+
+/// Scalars defined by the GraphQL specification
+const BUILTIN_SCALARS: &[&str] = &["Int", "Float", "String", "Boolean", "ID"];
+
+impl GraphQL {
+    /// Create a generator with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the scalar used for maps and arbitrary JSON values (`JSON` by default)
+    pub fn json_scalar(mut self, name: &str) -> Self {
+        self.json_scalar = name.to_owned();
+        self
+    }
+
+    /// Set the scalar used for integers outside the 32 bit range of `Int` (`String` by default)
+    pub fn big_integer_scalar(mut self, name: &str) -> Self {
+        self.big_integer_scalar = name.to_owned();
+        self
+    }
+
+    /// Set the scalar used for timestamps (`DateTime` by default)
+    pub fn datetime_scalar(mut self, name: &str) -> Self {
+        self.datetime_scalar = name.to_owned();
+        self
+    }
+
+    /// Also write an input type for every struct
+    pub fn inputs(mut self, inputs: bool) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    /// Generate definitions for every named type used by the given descriptors
+    pub fn generate<'a, Metadata: Default + GraphQLMetadata + 'a>(&self, roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> String {
+        let mut scalars = BTreeSet::new();
+        let mut definitions = BTreeMap::new();
+
+        for (name, descriptor) in named_types(roots) {
+            match &descriptor.kind {
+                Kind::Struct { children, .. } => {
+                    let output = children.iter().filter(|child| !child.skip_serializing);
+                    definitions.insert(name.to_owned(), self.object("type", name, &descriptor.docs, output, false, &mut scalars));
+                    if self.inputs {
+                        let input = children.iter().filter(|child| !child.skip_deserializing);
+                        let input_name = format!("{name}Input");
+                        definitions.insert(input_name.clone(), self.object("input", &input_name, &descriptor.docs, input, true, &mut scalars));
+                    }
+                },
+                Kind::Enum { variants, .. } => {
+                    let mut definition = String::new();
+                    write_description(&mut definition, "", &doc_lines(&descriptor.docs));
+                    let _ = writeln!(definition, "enum {name} {{");
+                    for variant in variants {
+                        write_description(&mut definition, "  ", &doc_lines(&variant.docs));
+                        let _ = writeln!(definition, "  {}{}", enum_value(&variant.label), deprecation(variant.metadata.deprecated()));
+                    }
+                    definition.push_str("}\n");
+                    definitions.insert(name.to_owned(), definition);
+                },
+                _ => {}
+            }
+        }
+
+        let mut output = String::new();
+        for scalar in scalars {
+            let _ = writeln!(output, "scalar {scalar}");
+        }
+        for definition in definitions.values() {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(definition);
+        }
+        output
+    }
+
+    /// Write an object or input type
+    fn object<'a, Metadata: Default + GraphQLMetadata + 'a>(&self, keyword: &str, name: &str, docs: &Option<Vec<Cow<'static, str>>>, children: impl Iterator<Item = &'a Entry<Metadata>>, input: bool, scalars: &mut BTreeSet<String>) -> String {
+        let mut output = String::new();
+        write_description(&mut output, "", &doc_lines(docs));
+        let _ = writeln!(output, "{keyword} {name} {{");
+        for child in children {
+            write_description(&mut output, "  ", &doc_lines(&child.docs));
+            let field_type = self.type_reference(&child.type_info, input, scalars);
+            // required input fields can't be deprecated
+            let deprecated = if input && field_type.ends_with('!') {
+                String::new()
+            } else {
+                deprecation(child.metadata.deprecated().or_else(|| type_deprecation(&child.type_info)))
+            };
+            let _ = writeln!(output, "  {}: {field_type}{deprecated}", field_name(&child.label, '_'));
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    /// Render the type of a value, non null unless it is optional
+    fn type_reference<Metadata: Default>(&self, descriptor: &Descriptor<Metadata>, input: bool, scalars: &mut BTreeSet<String>) -> String {
+        match &descriptor.kind {
+            Kind::Aliased { kind, .. } => self.type_reference(kind, input, scalars),
+            Kind::Option(kind) => {
+                let inner = self.type_reference(kind, input, scalars);
+                inner.strip_suffix('!').map(str::to_owned).unwrap_or(inner)
+            },
+            kind => format!("{}!", self.named_type(kind, input, scalars)),
+        }
+    }
+
+    /// Render the name of a type without its nullability
+    fn named_type<Metadata: Default>(&self, kind: &Kind<Metadata>, input: bool, scalars: &mut BTreeSet<String>) -> String {
+        let scalar = match kind {
+            Kind::Struct { name, .. } => return if input { format!("{name}Input") } else { name.to_string() },
+            Kind::Enum { name, .. } => return name.to_string(),
+            Kind::Aliased { kind, .. } => return self.named_type(&kind.kind, input, scalars),
+            Kind::Option(kind) => return self.named_type(&kind.kind, input, scalars),
+            Kind::Sequence(kind) => return format!("[{}]", self.type_reference(kind, input, scalars)),
+            Kind::Mapping(..) | Kind::JSON | Kind::Any => &self.json_scalar,
+            Kind::DateTime => &self.datetime_scalar,
+            Kind::String => "String",
+            Kind::I32 | Kind::U16 | Kind::I16 | Kind::U8 | Kind::I8 => "Int",
+            Kind::U128 | Kind::I128 | Kind::U64 | Kind::I64 | Kind::U32 => &self.big_integer_scalar,
+            Kind::F64 | Kind::F32 => "Float",
+            Kind::Bool => "Boolean",
+        };
+        if !BUILTIN_SCALARS.contains(&scalar) {
+            scalars.insert(scalar.to_owned());
+        }
+        scalar.to_owned()
+    }
+}
+
+/// Find the deprecation of the named type a value refers to
+fn type_deprecation<Metadata: Default + GraphQLMetadata>(descriptor: &Descriptor<Metadata>) -> Option<Option<String>> {
+    descriptor.metadata.deprecated().or_else(|| match &descriptor.kind {
+        Kind::Aliased { kind, .. } | Kind::Option(kind) | Kind::Sequence(kind) => type_deprecation(kind),
+        _ => None,
+    })
+}
+
+/// Render the `@deprecated` directive for a field or enum value
+fn deprecation(deprecated: Option<Option<String>>) -> String {
+    match deprecated {
+        None => String::new(),
+        Some(None) => " @deprecated".to_owned(),
+        Some(Some(reason)) => format!(" @deprecated(reason: {})", string_literal(&reason)),
+    }
+}
+
+/// Write doc strings as a description
+fn write_description(output: &mut String, indent: &str, lines: &[&str]) {
+    match lines {
+        [] => {},
+        [line] => {
+            let _ = writeln!(output, "{indent}{}", string_literal(line));
+        },
+        lines => {
+            let _ = writeln!(output, "{indent}\"\"\"");
+            for line in lines {
+                if line.is_empty() {
+                    output.push('\n');
+                } else {
+                    let _ = writeln!(output, "{indent}{}", line.replace("\"\"\"", "\\\"\"\""));
+                }
+            }
+            let _ = writeln!(output, "{indent}\"\"\"");
+        }
+    }
+}
+
+/// Build an upper case enum value name from a variant label
+fn enum_value(label: &str) -> String {
+    field_name(&constant_name(label), '_')
+}
@@ -0,0 +1,597 @@
+//! Export descriptors as JSON Schema (draft 2020-12) documents.
+//!
+//! Structs, enums and aliased types are written once into `$defs` and referenced from wherever
+//! they are used, the outermost type is written inline at the root of the document.
+
+use core::fmt;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::{Descriptor, Entry, Kind, Variant};
+
+/// Identifier of the JSON Schema dialect being produced
+pub const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Function used to add custom keywords to a schema based on metadata
+type Hook<'a, Metadata> = dyn Fn(&Metadata, &mut Map<String, Value>) + 'a;
+
+/// Configurable JSON Schema generator
+pub struct JsonSchema<'a, Metadata> {
+    /// Prefix used for references to named types
+    reference_prefix: String,
+    /// User function called for each type and field schema
+    hook: Option<Box<Hook<'a, Metadata>>>,
+}
+
+impl<Metadata> Default for JsonSchema<'_, Metadata> {
+    fn default() -> Self {
+        Self {
+            reference_prefix: "#/$defs/".to_owned(),
+            hook: None,
+        }
+    }
+}
+
+impl<'a, Metadata: Default> JsonSchema<'a, Metadata> {
+    /// Create a generator with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a function that can insert extra keywords into a schema based on metadata.
+    ///
+    /// The hook is called with the type metadata for every type schema, and with the field
+    /// metadata for every property schema.
+    pub fn with_hook(mut self, hook: impl Fn(&Metadata, &mut Map<String, Value>) + 'a) -> Self {
+        self.hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Change the prefix used when referencing named types (`#/$defs/` by default)
+    pub fn reference_prefix(mut self, prefix: &str) -> Self {
+        self.reference_prefix = prefix.to_owned();
+        self
+    }
+
+    /// Build a complete schema document for a type
+    pub fn generate(&self, descriptor: &Descriptor<Metadata>) -> Value {
+        let mut definitions = Map::new();
+        let mut root = match self.inline(descriptor, &mut definitions) {
+            Value::Object(root) => root,
+            other => {
+                let mut root = Map::new();
+                root.insert("allOf".to_owned(), json!([other]));
+                root
+            }
+        };
+        if let Some(name) = named(&descriptor.kind) {
+            root.insert("title".to_owned(), json!(name));
+        }
+
+        let mut document = Map::new();
+        document.insert("$schema".to_owned(), json!(DRAFT_2020_12));
+        document.append(&mut root);
+        if !definitions.is_empty() {
+            document.insert("$defs".to_owned(), Value::Object(definitions));
+        }
+        Value::Object(document)
+    }
+
+    /// Build the schema for a type, placing any named types it uses into the given definitions.
+    ///
+    /// Named types are returned as references using the configured prefix, this can be
+    /// used to assemble documents containing many types such as OpenAPI components.
+    pub fn schema(&self, descriptor: &Descriptor<Metadata>, definitions: &mut Map<String, Value>) -> Value {
+        match named(&descriptor.kind) {
+            Some(name) => {
+                if !definitions.contains_key(name) {
+                    // Reserve the name first so that recursive references terminate
+                    definitions.insert(name.to_owned(), Value::Bool(true));
+                    let schema = self.inline(descriptor, definitions);
+                    definitions.insert(name.to_owned(), schema);
+                }
+                json!({"$ref": format!("{}{name}", self.reference_prefix)})
+            },
+            None => self.inline(descriptor, definitions),
+        }
+    }
+
+    /// Build the schema for a type without replacing it with a reference
+    fn inline(&self, descriptor: &Descriptor<Metadata>, definitions: &mut Map<String, Value>) -> Value {
+        let mut schema = match &descriptor.kind {
+            Kind::Struct { children, .. } => self.object(children, definitions),
+            Kind::Aliased { kind, .. } => self.schema(kind, definitions),
+            Kind::Enum { variants, .. } => {
+                let mut labels = vec![];
+                for variant in variants {
+                    for label in Some(&variant.label).into_iter().chain(&variant.aliases) {
+                        if !labels.contains(&label.as_ref()) {
+                            labels.push(label.as_ref());
+                        }
+                    }
+                }
+                json!({"type": "string", "enum": labels})
+            },
+            Kind::Sequence(kind) => json!({"type": "array", "items": self.schema(kind, definitions)}),
+            Kind::Option(kind) => nullable(self.schema(kind, definitions)),
+            Kind::Mapping(key, value) => {
+                let mut schema = json!({"type": "object", "additionalProperties": self.schema(value, definitions)});
+                if let Some(names) = property_names(key, self, definitions) {
+                    schema["propertyNames"] = names;
+                }
+                schema
+            },
+            Kind::DateTime => json!({"type": "string", "format": "date-time"}),
+            Kind::String => json!({"type": "string"}),
+            Kind::U128 => json!({"type": "integer", "minimum": 0}),
+            Kind::I128 => json!({"type": "integer"}),
+            Kind::U64 => json!({"type": "integer", "minimum": 0, "maximum": u64::MAX}),
+            Kind::I64 => json!({"type": "integer", "minimum": i64::MIN, "maximum": i64::MAX}),
+            Kind::U32 => json!({"type": "integer", "minimum": 0, "maximum": u32::MAX}),
+            Kind::I32 => json!({"type": "integer", "minimum": i32::MIN, "maximum": i32::MAX}),
+            Kind::U16 => json!({"type": "integer", "minimum": 0, "maximum": u16::MAX}),
+            Kind::I16 => json!({"type": "integer", "minimum": i16::MIN, "maximum": i16::MAX}),
+            Kind::U8 => json!({"type": "integer", "minimum": 0, "maximum": u8::MAX}),
+            Kind::I8 => json!({"type": "integer", "minimum": i8::MIN, "maximum": i8::MAX}),
+            Kind::F64 | Kind::F32 => json!({"type": "number"}),
+            Kind::Bool => json!({"type": "boolean"}),
+            Kind::JSON | Kind::Any => json!({}),
+        };
+
+        if let Value::Object(schema) = &mut schema {
+            if let Some(docs) = &descriptor.docs {
+                schema.insert("description".to_owned(), json!(docs.join("\n")));
+            }
+            if let Some(hook) = &self.hook {
+                hook(&descriptor.metadata, schema);
+            }
+        }
+        schema
+    }
+
+    /// Build the schema for the fields of a struct
+    fn object(&self, children: &[Entry<Metadata>], definitions: &mut Map<String, Value>) -> Value {
+        let mut properties = Map::new();
+        let mut required = vec![];
+        let mut alternatives = vec![];
+
+        for child in children {
+            // Fields skipped in both directions never appear in data
+            if child.skip_serializing && child.skip_deserializing {
+                continue
+            }
+            let mut schema = self.property(child, definitions);
+            if let Some(hook) = &self.hook {
+                hook(&child.metadata, &mut schema);
+            }
+            let schema = Value::Object(schema);
+
+            let aliases: Vec<_> = child.aliases.iter().filter(|alias| **alias != child.label).collect();
+            for alias in &aliases {
+                properties.insert(alias.to_string(), schema.clone());
+            }
+            properties.insert(child.label.to_string(), schema);
+
+            if !child.has_default && !child.skip_deserializing && !matches!(child.type_info.kind, Kind::Option(_)) {
+                if aliases.is_empty() {
+                    required.push(child.label.as_ref());
+                } else {
+                    // Any one of the names this field goes by satisfies the requirement
+                    let options: Vec<_> = Some(&child.label).into_iter().chain(aliases).map(|name| json!({"required": [name]})).collect();
+                    alternatives.push(json!({"anyOf": options}));
+                }
+            }
+        }
+
+        let mut schema = json!({"type": "object", "properties": properties});
+        if !required.is_empty() {
+            schema["required"] = json!(required);
+        }
+        match alternatives.len() {
+            0 => {},
+            1 => if let (Value::Object(schema), Some(Value::Object(alternative))) = (&mut schema, alternatives.pop()) {
+                schema.extend(alternative);
+            },
+            _ => schema["allOf"] = Value::Array(alternatives),
+        }
+        schema
+    }
+
+    /// Build the schema for a single field
+    fn property(&self, child: &Entry<Metadata>, definitions: &mut Map<String, Value>) -> Map<String, Value> {
+        let mut schema = match self.schema(&child.type_info, definitions) {
+            Value::Object(schema) => schema,
+            _ => Map::new(),
+        };
+        if let Some(docs) = &child.docs {
+            schema.insert("description".to_owned(), json!(docs.join("\n")));
+        }
+        if child.skip_deserializing {
+            schema.insert("readOnly".to_owned(), json!(true));
+        }
+        if child.skip_serializing {
+            schema.insert("writeOnly".to_owned(), json!(true));
+        }
+        schema
+    }
+}
+
+impl<Metadata: Default> Descriptor<Metadata> {
+    /// Build a JSON Schema document for this type with the default generator settings
+    pub fn json_schema(&self) -> Value {
+        JsonSchema::new().generate(self)
+    }
+}
+
+/// Name of types that should be placed in the definitions section
+fn named<Metadata: Default>(kind: &Kind<Metadata>) -> Option<&str> {
+    match kind {
+        Kind::Struct { name, .. } | Kind::Enum { name, .. } | Kind::Aliased { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+/// Allow null in addition to the values accepted by a schema
+fn nullable(schema: Value) -> Value {
+    if let Value::Object(map) = &schema {
+        if map.len() == 1 {
+            if let Some(Value::String(kind)) = map.get("type") {
+                return json!({"type": [kind, "null"]});
+            }
+        }
+    }
+    json!({"anyOf": [schema, {"type": "null"}]})
+}
+
+/// Constraints on the keys of a mapping, object keys are always strings in JSON
+fn property_names<Metadata: Default>(key: &Descriptor<Metadata>, generator: &JsonSchema<'_, Metadata>, definitions: &mut Map<String, Value>) -> Option<Value> {
+    match &key.kind {
+        Kind::Enum { .. } => Some(generator.schema(key, definitions)),
+        Kind::Aliased { kind, .. } => property_names(kind, generator, definitions),
+        Kind::U128 | Kind::U64 | Kind::U32 | Kind::U16 | Kind::U8 => Some(json!({"pattern": "^[0-9]+$"})),
+        Kind::I128 | Kind::I64 | Kind::I32 | Kind::I16 | Kind::I8 => Some(json!({"pattern": "^-?[0-9]+$"})),
+        _ => None,
+    }
+}
+
+/// A part of a schema that couldn't be imported
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ImportIssue {
+    /// JSON Pointer to the schema that couldn't be imported
+    pub pointer: String,
+    /// Why it couldn't be imported
+    pub message: String,
+}
+
+impl fmt::Display for ImportIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "(root): {}", self.message)
+        } else {
+            write!(f, "{}: {}", self.pointer, self.message)
+        }
+    }
+}
+
+/// A descriptor built from a schema along with the parts of the schema that couldn't be mapped
+#[derive(Debug, Clone)]
+pub struct Imported<Metadata: Default> {
+    /// Type described by the schema, parts that couldn't be mapped are described as `Kind::JSON`
+    pub descriptor: Descriptor<Metadata>,
+    /// Problems found importing the schema
+    pub issues: Vec<ImportIssue>,
+}
+
+/// Build a descriptor from a JSON Schema document.
+///
+/// Keywords that describe structure are mapped onto kinds, definitions referenced with `$ref`
+/// become named types and keywords without a mapping, such as `minLength` or `pattern`, are
+/// placed in metadata as key and JSON text pairs. String values keep their quotes, matching
+/// the values the derive macro captures. Constraints written on a property are placed in the
+/// metadata of the entry, other constraints in the metadata of the type.
+///
+/// The imported descriptor owns its strings, so metadata has to accept owned pairs.
+pub fn import<Metadata: Default + Clone + Extend<(String, String)>>(document: &Value) -> Imported<Metadata> {
+    let mut importer = Importer::<Metadata> { document, definitions: HashMap::new(), resolving: vec![], issues: vec![] };
+    let name = document.get("title").and_then(Value::as_str).unwrap_or("Root");
+    let (mut descriptor, unmapped) = importer.schema(document, "", name);
+    descriptor.metadata.extend(unmapped);
+    Imported { descriptor, issues: importer.issues }
+}
+
+/// Keywords consumed by the importer, or without meaning for a descriptor
+const IMPORTED_KEYWORDS: &[&str] = &[
+    "$schema", "$id", "$defs", "definitions", "$ref", "title", "description", "type", "properties",
+    "required", "enum", "items", "additionalProperties", "propertyNames", "anyOf", "oneOf",
+];
+
+/// State kept while importing a schema document
+struct Importer<'a, Metadata: Default> {
+    /// Document references are resolved against
+    document: &'a Value,
+    /// Definitions already imported, by reference
+    definitions: HashMap<String, Descriptor<Metadata>>,
+    /// References being imported, used to detect recursion
+    resolving: Vec<String>,
+    /// Problems found so far
+    issues: Vec<ImportIssue>,
+}
+
+/// Metadata pairs built from unmapped keywords
+type Unmapped = Vec<(String, String)>;
+
+impl<Metadata: Default + Clone + Extend<(String, String)>> Importer<'_, Metadata> {
+    /// Record a problem and describe the value as arbitrary JSON
+    fn issue(&mut self, pointer: &str, message: impl Into<String>) -> Kind<Metadata> {
+        self.issues.push(ImportIssue { pointer: pointer.to_owned(), message: message.into() });
+        Kind::JSON
+    }
+
+    /// Import a schema, `name` is used if the schema describes a struct or enum without a title
+    fn schema(&mut self, schema: &Value, pointer: &str, name: &str) -> (Descriptor<Metadata>, Unmapped) {
+        let schema = match schema {
+            Value::Object(schema) => schema,
+            Value::Bool(true) => return (descriptor(None, Kind::JSON), vec![]),
+            Value::Bool(false) => return (descriptor(None, self.issue(pointer, "a schema that accepts nothing can't be described")), vec![]),
+            _ => return (descriptor(None, self.issue(pointer, "expected a schema object")), vec![]),
+        };
+        let docs = description(schema.get("description"));
+        let mut unmapped: Unmapped = schema.iter()
+            .filter(|(key, _)| !IMPORTED_KEYWORDS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.to_string()))
+            .collect();
+
+        if let Some(reference) = schema.get("$ref") {
+            let mut descriptor = match reference.as_str() {
+                Some(reference) => self.reference(reference, pointer),
+                None => descriptor(None, self.issue(&format!("{pointer}/$ref"), "expected a string")),
+            };
+            if docs.is_some() {
+                descriptor.docs = docs;
+            }
+            return (descriptor, unmapped)
+        }
+        let name = schema.get("title").and_then(Value::as_str).unwrap_or(name);
+
+        let mut types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(kind)) => vec![kind.as_str()],
+            Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        let mut nullable = types.contains(&"null");
+        let only_null = nullable && types.iter().all(|kind| *kind == "null");
+        types.retain(|kind| *kind != "null");
+
+        let kind = if let Some(values) = schema.get("enum") {
+            let values = values.as_array().map(Vec::as_slice).unwrap_or_default();
+            nullable |= values.contains(&Value::Null);
+            let labels: Option<Vec<&str>> = values.iter().filter(|value| !value.is_null()).map(Value::as_str).collect();
+            match labels {
+                Some(labels) => Kind::Enum {
+                    name: name.to_owned().into(),
+                    variants: labels.into_iter().map(|label| Variant { label: label.to_owned().into(), docs: None, metadata: Default::default(), aliases: vec![label.to_owned().into()] }).collect(),
+                },
+                None => self.issue(&format!("{pointer}/enum"), "only enums of strings can be described"),
+            }
+        } else if let Some((keyword, options)) = ["anyOf", "oneOf"].iter().find_map(|keyword| Some((*keyword, schema.get(*keyword)?))) {
+            let options = options.as_array().map(Vec::as_slice).unwrap_or_default();
+            let not_null: Vec<_> = options.iter().enumerate().filter(|(_, option)| option.get("type") != Some(&json!("null"))).collect();
+            if types.is_empty() && not_null.len() == 1 && options.len() == 2 {
+                let (index, option) = not_null[0];
+                let (inner, inner_unmapped) = self.schema(option, &format!("{pointer}/{keyword}/{index}"), name);
+                unmapped.extend(inner_unmapped);
+                Kind::Option(Box::new(inner))
+            } else if schema.contains_key("properties") {
+                // alternatives that only constrain the fields of a struct are kept as metadata
+                unmapped.push((keyword.to_owned(), Value::Array(options.to_vec()).to_string()));
+                self.object(schema, pointer, name, &mut unmapped)
+            } else {
+                self.issue(&format!("{pointer}/{keyword}"), "alternatives other than null can't be described")
+            }
+        } else {
+            match types.as_slice() {
+                [] if schema.contains_key("properties") || schema.contains_key("additionalProperties") => self.object(schema, pointer, name, &mut unmapped),
+                [] if schema.contains_key("items") => self.array(schema, pointer, name),
+                [] if only_null => self.issue(&format!("{pointer}/type"), "a null value on its own can't be described"),
+                [] => Kind::JSON,
+                ["object"] => self.object(schema, pointer, name, &mut unmapped),
+                ["array"] => self.array(schema, pointer, name),
+                ["string"] => match schema.get("format").and_then(Value::as_str) {
+                    Some("date-time") => {
+                        unmapped.retain(|(key, _)| *key != "format");
+                        Kind::DateTime
+                    },
+                    _ => Kind::String,
+                },
+                ["integer"] => integer(schema, &mut unmapped),
+                ["number"] => match schema.get("format").and_then(Value::as_str) {
+                    Some("float") => {
+                        unmapped.retain(|(key, _)| *key != "format");
+                        Kind::F32
+                    },
+                    _ => Kind::F64,
+                },
+                ["boolean"] => Kind::Bool,
+                [other] => self.issue(&format!("{pointer}/type"), format!("unknown type {other}")),
+                _ => self.issue(&format!("{pointer}/type"), "values of more than one type can't be described"),
+            }
+        };
+
+        let kind = if nullable && !matches!(kind, Kind::Option(_)) { Kind::Option(Box::new(descriptor(None, kind))) } else { kind };
+        (descriptor(docs, kind), unmapped)
+    }
+
+    /// Import a struct or mapping
+    fn object(&mut self, schema: &Map<String, Value>, pointer: &str, name: &str, unmapped: &mut Unmapped) -> Kind<Metadata> {
+        let Some(properties) = schema.get("properties") else {
+            let value = match schema.get("additionalProperties") {
+                Some(value) => {
+                    let (mut value, value_unmapped) = self.schema(value, &format!("{pointer}/additionalProperties"), &format!("{name}Value"));
+                    value.metadata.extend(value_unmapped);
+                    value
+                },
+                None => descriptor(None, Kind::JSON),
+            };
+            let key = match schema.get("propertyNames") {
+                Some(names) if names.get("enum").is_some() || names.get("$ref").is_some() => {
+                    let (mut key, key_unmapped) = self.schema(names, &format!("{pointer}/propertyNames"), &format!("{name}Key"));
+                    key.metadata.extend(key_unmapped);
+                    key
+                },
+                Some(names) => {
+                    unmapped.push(("propertyNames".to_owned(), names.to_string()));
+                    descriptor(None, Kind::String)
+                },
+                None => descriptor(None, Kind::String),
+            };
+            return Kind::Mapping(Box::new(key), Box::new(value))
+        };
+
+        let Some(properties) = properties.as_object() else {
+            return self.issue(&format!("{pointer}/properties"), "expected an object")
+        };
+        if let Some(additional) = schema.get("additionalProperties") {
+            unmapped.push(("additionalProperties".to_owned(), additional.to_string()));
+        }
+        let required: Vec<&str> = schema.get("required").and_then(Value::as_array).map(|names| names.iter().filter_map(Value::as_str).collect()).unwrap_or_default();
+        for name in &required {
+            if !properties.contains_key(*name) {
+                self.issue(&format!("{pointer}/required"), format!("required property {name} isn't defined"));
+            }
+        }
+
+        let mut children = vec![];
+        for (label, property) in properties {
+            let property_pointer = format!("{pointer}/properties/{}", escape_pointer(label));
+            let (mut type_info, entry_unmapped) = self.schema(property, &property_pointer, &format!("{name}{}", pascal_case(label)));
+            // the description of a property documents the field rather than its type
+            let docs = match property.get("$ref") {
+                Some(_) => description(property.get("description")),
+                None => type_info.docs.take(),
+            };
+            let mut metadata = Metadata::default();
+            metadata.extend(entry_unmapped.into_iter().filter(|(key, _)| !["readOnly", "writeOnly"].contains(&key.as_str())));
+            let optional = matches!(type_info.kind, Kind::Option(_));
+            children.push(Entry {
+                label: label.clone().into(),
+                docs,
+                metadata,
+                has_default: property.get("default").is_some() || (!optional && !required.contains(&label.as_str())),
+                aliases: vec![label.clone().into()],
+                skip_serializing: property.get("writeOnly") == Some(&Value::Bool(true)),
+                skip_deserializing: property.get("readOnly") == Some(&Value::Bool(true)),
+                type_info,
+            });
+        }
+        Kind::Struct { name: name.to_owned().into(), children }
+    }
+
+    /// Import a list
+    fn array(&mut self, schema: &Map<String, Value>, pointer: &str, name: &str) -> Kind<Metadata> {
+        match schema.get("items") {
+            Some(Value::Array(_)) => self.issue(&format!("{pointer}/items"), "tuples can't be described"),
+            Some(items) => {
+                let (mut items, unmapped) = self.schema(items, &format!("{pointer}/items"), &format!("{name}Item"));
+                items.metadata.extend(unmapped);
+                Kind::Sequence(Box::new(items))
+            },
+            None if schema.contains_key("prefixItems") => self.issue(&format!("{pointer}/prefixItems"), "tuples can't be described"),
+            None => Kind::Sequence(Box::new(descriptor(None, Kind::JSON))),
+        }
+    }
+
+    /// Import a referenced definition as a named type
+    fn reference(&mut self, reference: &str, pointer: &str) -> Descriptor<Metadata> {
+        if let Some(descriptor) = self.definitions.get(reference) {
+            return descriptor.clone()
+        }
+        let Some(path) = reference.strip_prefix('#') else {
+            return descriptor(None, self.issue(&format!("{pointer}/$ref"), format!("only references within the document are supported, found {reference}")))
+        };
+        let Some(target) = self.document.pointer(path) else {
+            return descriptor(None, self.issue(&format!("{pointer}/$ref"), format!("{reference} doesn't exist")))
+        };
+        if self.resolving.iter().any(|resolving| resolving == reference) {
+            return descriptor(None, self.issue(&format!("{pointer}/$ref"), format!("recursive reference to {reference} can't be described")))
+        }
+
+        let name = path.rsplit('/').next().unwrap_or_default().replace("~1", "/").replace("~0", "~");
+        self.resolving.push(reference.to_owned());
+        let (mut definition, unmapped) = self.schema(target, path, &name);
+        self.resolving.pop();
+        definition.metadata.extend(unmapped);
+
+        let definition = match definition.kind {
+            Kind::Struct { .. } | Kind::Enum { .. } => definition,
+            kind => Descriptor {
+                docs: definition.docs,
+                metadata: definition.metadata,
+                kind: Kind::Aliased { name: name.into(), kind: Box::new(self::descriptor(None, kind)) },
+            },
+        };
+        self.definitions.insert(reference.to_owned(), definition.clone());
+        definition
+    }
+}
+
+/// Build a descriptor with default metadata
+fn descriptor<Metadata: Default>(docs: Option<Vec<Cow<'static, str>>>, kind: Kind<Metadata>) -> Descriptor<Metadata> {
+    Descriptor { docs, metadata: Default::default(), kind }
+}
+
+/// Pick the integer kind whose range matches the bounds of a schema
+fn integer<Metadata: Default>(schema: &Map<String, Value>, unmapped: &mut Unmapped) -> Kind<Metadata> {
+    let ranges: [(Kind<Metadata>, i128, i128); 8] = [
+        (Kind::U64, 0, u64::MAX as i128),
+        (Kind::I64, i64::MIN as i128, i64::MAX as i128),
+        (Kind::U32, 0, u32::MAX as i128),
+        (Kind::I32, i32::MIN as i128, i32::MAX as i128),
+        (Kind::U16, 0, u16::MAX as i128),
+        (Kind::I16, i16::MIN as i128, i16::MAX as i128),
+        (Kind::U8, 0, u8::MAX as i128),
+        (Kind::I8, i8::MIN as i128, i8::MAX as i128),
+    ];
+    let bound = |key: &str| schema.get(key).and_then(|value| value.as_i64().map(i128::from).or_else(|| value.as_u64().map(i128::from)));
+    if let (Some(minimum), Some(maximum)) = (bound("minimum"), bound("maximum")) {
+        for (kind, low, high) in ranges {
+            if minimum == low && maximum == high {
+                unmapped.retain(|(key, _)| *key != "minimum" && *key != "maximum");
+                return kind
+            }
+        }
+    }
+    match schema.get("format").and_then(Value::as_str) {
+        Some("int32") => {
+            unmapped.retain(|(key, _)| *key != "format");
+            Kind::I32
+        },
+        _ => Kind::I64,
+    }
+}
+
+/// Split a description into owned doc lines
+fn description(value: Option<&Value>) -> Option<Vec<Cow<'static, str>>> {
+    value.and_then(Value::as_str).map(|text| text.lines().map(|line| Cow::Owned(line.to_owned())).collect())
+}
+
+/// Escape a key for use in a JSON Pointer
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Build a type name from a property name
+fn pascal_case(label: &str) -> String {
+    let mut output = String::new();
+    let mut upper = true;
+    for c in label.chars() {
+        if c.is_ascii_alphanumeric() {
+            output.push(if upper { c.to_ascii_uppercase() } else { c });
+            upper = false;
+        } else {
+            upper = true;
+        }
+    }
+    output
+}
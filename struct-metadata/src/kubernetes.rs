@@ -0,0 +1,497 @@
+//! Generate Kubernetes CustomResourceDefinitions from descriptors.
+//!
+//! The spec and status types of a resource are written inline into an `openAPIV3Schema` that
+//! follows the structural schema rules: every node has a type, nothing is referenced with
+//! `$ref` and arbitrary JSON values are marked with `x-kubernetes-preserve-unknown-fields`.
+//! Names, version and printer columns are read from the metadata of the spec type.
+
+use core::fmt;
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::named::{unalias, unquote};
+use crate::{Descriptor, Entry, Kind};
+
+/// Version of the CustomResourceDefinition API being produced
+pub const API_VERSION: &str = "apiextensions.k8s.io/v1";
+
+/// Metadata settings read by the CustomResourceDefinition generator
+pub trait KubernetesMetadata {
+    /// How a list field is merged, one of `atomic`, `set` or `map`
+    fn list_type(&self) -> Option<String> { None }
+    /// Fields identifying the items of a list with the `map` list type
+    fn list_map_keys(&self) -> Vec<String> { vec![] }
+    /// API group of the resource, read from the spec type
+    fn group(&self) -> Option<String> { None }
+    /// Version of the resource, read from the spec type
+    fn version(&self) -> Option<String> { None }
+    /// Kind of the resource, read from the spec type
+    fn resource_kind(&self) -> Option<String> { None }
+    /// Plural name of the resource, read from the spec type
+    fn plural(&self) -> Option<String> { None }
+    /// Short names of the resource, read from the spec type
+    fn short_names(&self) -> Vec<String> { vec![] }
+    /// Is the resource cluster scoped rather than namespaced, read from the spec type
+    fn cluster_scoped(&self) -> bool { false }
+    /// Printer columns as pairs of a name and a JSON path, read from the spec type
+    fn printer_columns(&self) -> Vec<(String, String)> { vec![] }
+}
+
+/// Split a comma separated metadata value
+fn split(value: &str) -> Vec<String> {
+    unquote(value).split(',').map(|item| item.trim().to_owned()).filter(|item| !item.is_empty()).collect()
+}
+
+/// Reads the `list_type`, `list_map_keys` (comma separated), `group`, `version`, `kind`,
+/// `plural`, `short_names` (comma separated), `scope` (`Namespaced` or `Cluster`) and
+/// `printer_columns` keys. Printer columns are written as comma separated `Name=.json.path`
+/// pairs.
+impl<K: AsRef<str>, V: AsRef<str>> KubernetesMetadata for HashMap<K, V> {
+    fn list_type(&self) -> Option<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "list_type").map(|(_, value)| unquote(value.as_ref()).to_owned())
+    }
+
+    fn list_map_keys(&self) -> Vec<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "list_map_keys").map(|(_, value)| split(value.as_ref())).unwrap_or_default()
+    }
+
+    fn group(&self) -> Option<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "group").map(|(_, value)| unquote(value.as_ref()).to_owned())
+    }
+
+    fn version(&self) -> Option<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "version").map(|(_, value)| unquote(value.as_ref()).to_owned())
+    }
+
+    fn resource_kind(&self) -> Option<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "kind").map(|(_, value)| unquote(value.as_ref()).to_owned())
+    }
+
+    fn plural(&self) -> Option<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "plural").map(|(_, value)| unquote(value.as_ref()).to_owned())
+    }
+
+    fn short_names(&self) -> Vec<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "short_names").map(|(_, value)| split(value.as_ref())).unwrap_or_default()
+    }
+
+    fn cluster_scoped(&self) -> bool {
+        self.iter().any(|(key, value)| key.as_ref() == "scope" && unquote(value.as_ref()) == "Cluster")
+    }
+
+    fn printer_columns(&self) -> Vec<(String, String)> {
+        let Some((_, value)) = self.iter().find(|(key, _)| key.as_ref() == "printer_columns") else { return vec![] };
+        split(value.as_ref()).into_iter().filter_map(|column| {
+            let (name, path) = column.split_once('=')?;
+            Some((name.trim().to_owned(), path.trim().to_owned()))
+        }).collect()
+    }
+}
+
+/// Errors building a CustomResourceDefinition
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CrdError {
+    /// Spec and status types must be structs
+    NotAStruct {
+        /// Type that was given
+        name: String,
+    },
+    /// Structural schemas can't refer to themselves
+    Recursive {
+        /// Type that contains itself
+        name: String,
+    },
+    /// A required setting wasn't found in the spec type metadata
+    MissingSetting {
+        /// Name of the setting
+        setting: &'static str,
+    },
+    /// A list type setting that can't be applied to the field
+    InvalidListType {
+        /// Path of the field the setting was given for
+        path: String,
+        /// Why the setting can't be used
+        reason: String,
+    },
+    /// A printer column path that doesn't lead to a field
+    UnknownColumnPath {
+        /// Path that was given
+        path: String,
+    },
+}
+
+impl fmt::Display for CrdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrdError::NotAStruct { name } => write!(f, "{name} is not a struct"),
+            CrdError::Recursive { name } => write!(f, "{name} contains itself, which structural schemas can't express"),
+            CrdError::MissingSetting { setting } => write!(f, "the {setting} setting is required"),
+            CrdError::InvalidListType { path, reason } => write!(f, "{path}: {reason}"),
+            CrdError::UnknownColumnPath { path } => write!(f, "printer column path {path} doesn't lead to a field"),
+        }
+    }
+}
+
+impl std::error::Error for CrdError {}
+
+/// CustomResourceDefinition generator for a spec type and an optional status type
+pub struct Crd<'a, Metadata: Default> {
+    /// Type of the `spec` field
+    spec: &'a Descriptor<Metadata>,
+    /// Type of the `status` field
+    status: Option<&'a Descriptor<Metadata>>,
+}
+
+impl<'a, Metadata: Default + KubernetesMetadata> Crd<'a, Metadata> {
+    /// Create a generator for a resource with the given spec type
+    pub fn new(spec: &'a Descriptor<Metadata>) -> Self {
+        Self { spec, status: None }
+    }
+
+    /// Add a status type, this also enables the status subresource
+    pub fn status(mut self, status: &'a Descriptor<Metadata>) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Build the `openAPIV3Schema` of the resource
+    pub fn schema(&self) -> Result<Value, CrdError> {
+        let mut properties = Map::new();
+        properties.insert("spec".to_owned(), self.root(self.spec, "spec")?);
+        if let Some(status) = self.status {
+            properties.insert("status".to_owned(), self.root(status, "status")?);
+        }
+        Ok(json!({
+            "type": "object",
+            "properties": properties,
+            "required": ["spec"],
+        }))
+    }
+
+    /// Build the complete CustomResourceDefinition
+    pub fn definition(&self) -> Result<Value, CrdError> {
+        let metadata = &self.spec.metadata;
+        let group = metadata.group().ok_or(CrdError::MissingSetting { setting: "group" })?;
+        let version = metadata.version().ok_or(CrdError::MissingSetting { setting: "version" })?;
+        let kind = match (metadata.resource_kind(), &self.spec.kind) {
+            (Some(kind), _) => kind,
+            (None, Kind::Struct { name, .. }) => name.strip_suffix("Spec").filter(|name| !name.is_empty()).unwrap_or(name).to_owned(),
+            (None, _) => return Err(CrdError::MissingSetting { setting: "kind" }),
+        };
+        let singular = kind.to_lowercase();
+        let plural = metadata.plural().unwrap_or_else(|| format!("{singular}s"));
+
+        let mut names = json!({
+            "kind": kind,
+            "listKind": format!("{kind}List"),
+            "plural": plural,
+            "singular": singular,
+        });
+        let short_names = metadata.short_names();
+        if !short_names.is_empty() {
+            names["shortNames"] = json!(short_names);
+        }
+
+        let mut version = json!({
+            "name": version,
+            "served": true,
+            "storage": true,
+            "schema": {"openAPIV3Schema": self.schema()?},
+        });
+        if self.status.is_some() {
+            version["subresources"] = json!({"status": {}});
+        }
+        let mut columns = vec![];
+        for (name, path) in metadata.printer_columns() {
+            columns.push(json!({"name": name, "type": self.column_type(&path)?, "jsonPath": path}));
+        }
+        if !columns.is_empty() {
+            version["additionalPrinterColumns"] = Value::Array(columns);
+        }
+
+        Ok(json!({
+            "apiVersion": API_VERSION,
+            "kind": "CustomResourceDefinition",
+            "metadata": {"name": format!("{plural}.{group}")},
+            "spec": {
+                "group": group,
+                "names": names,
+                "scope": if metadata.cluster_scoped() { "Cluster" } else { "Namespaced" },
+                "versions": [version],
+            },
+        }))
+    }
+
+    /// Build the complete CustomResourceDefinition as a YAML document
+    pub fn yaml(&self) -> Result<String, CrdError> {
+        Ok(yaml(&self.definition()?))
+    }
+
+    /// Build the schema of the spec or status type
+    fn root(&self, descriptor: &Descriptor<Metadata>, path: &str) -> Result<Value, CrdError> {
+        match &descriptor.kind {
+            Kind::Struct { .. } => schema(descriptor, path, &mut vec![]),
+            Kind::Aliased { kind, .. } => self.root(kind, path),
+            kind => Err(CrdError::NotAStruct { name: kind.type_expression() }),
+        }
+    }
+
+    /// Find the printer column type for a JSON path
+    fn column_type(&self, path: &str) -> Result<&'static str, CrdError> {
+        let unknown = || CrdError::UnknownColumnPath { path: path.to_owned() };
+        let mut parts = path.strip_prefix('.').ok_or_else(unknown)?.split('.');
+        let mut descriptor = match parts.next() {
+            Some("metadata") => return Ok(if path == ".metadata.creationTimestamp" { "date" } else { "string" }),
+            Some("spec") => self.spec,
+            Some("status") => self.status.ok_or_else(unknown)?,
+            _ => return Err(unknown()),
+        };
+        for part in parts {
+            descriptor = unwrap(descriptor);
+            let Kind::Struct { children, .. } = &descriptor.kind else { return Err(unknown()) };
+            descriptor = &children.iter().find(|child| child.label == part).ok_or_else(unknown)?.type_info;
+        }
+        Ok(match &unwrap(descriptor).kind {
+            Kind::U128 | Kind::I128 | Kind::U64 | Kind::I64 | Kind::U32 | Kind::I32 | Kind::U16 | Kind::I16 | Kind::U8 | Kind::I8 => "integer",
+            Kind::F64 | Kind::F32 => "number",
+            Kind::Bool => "boolean",
+            Kind::DateTime => "date",
+            _ => "string",
+        })
+    }
+}
+
+/// Look through aliased and optional types to the type they wrap
+fn unwrap<Metadata: Default>(mut descriptor: &Descriptor<Metadata>) -> &Descriptor<Metadata> {
+    while let Kind::Aliased { kind, .. } | Kind::Option(kind) = &descriptor.kind {
+        descriptor = kind;
+    }
+    descriptor
+}
+
+/// Build the structural schema for a type, `stack` holds the named types being written
+fn schema<'a, Metadata: Default + KubernetesMetadata>(descriptor: &'a Descriptor<Metadata>, path: &str, stack: &mut Vec<&'a str>) -> Result<Value, CrdError> {
+    let mut schema = match &descriptor.kind {
+        Kind::Struct { name, children } => {
+            if stack.contains(&name.as_ref()) {
+                return Err(CrdError::Recursive { name: name.to_string() })
+            }
+            stack.push(name);
+            let schema = object(children, path, stack);
+            stack.pop();
+            schema?
+        },
+        Kind::Aliased { name, kind } => {
+            if stack.contains(&name.as_ref()) {
+                return Err(CrdError::Recursive { name: name.to_string() })
+            }
+            stack.push(name);
+            let schema = schema(kind, path, stack);
+            stack.pop();
+            schema?
+        },
+        Kind::Enum { variants, .. } => {
+            let mut labels = vec![];
+            for variant in variants {
+                for label in Some(&variant.label).into_iter().chain(&variant.aliases) {
+                    if !labels.contains(&label.as_ref()) {
+                        labels.push(label.as_ref());
+                    }
+                }
+            }
+            json!({"type": "string", "enum": labels})
+        },
+        Kind::Sequence(kind) => json!({"type": "array", "items": schema(kind, &format!("{path}[]"), stack)?}),
+        Kind::Option(kind) => {
+            let mut schema = schema(kind, path, stack)?;
+            schema["nullable"] = json!(true);
+            schema
+        },
+        Kind::Mapping(_, value) => json!({"type": "object", "additionalProperties": schema(value, &format!("{path}{{}}"), stack)?}),
+        Kind::DateTime => json!({"type": "string", "format": "date-time"}),
+        Kind::String => json!({"type": "string"}),
+        Kind::U128 | Kind::I128 => json!({"type": "integer"}),
+        Kind::U64 | Kind::U32 | Kind::U16 | Kind::U8 => json!({"type": "integer", "format": "int64", "minimum": 0}),
+        Kind::I64 => json!({"type": "integer", "format": "int64"}),
+        Kind::I32 | Kind::I16 | Kind::I8 => json!({"type": "integer", "format": "int32"}),
+        Kind::F64 => json!({"type": "number", "format": "double"}),
+        Kind::F32 => json!({"type": "number", "format": "float"}),
+        Kind::Bool => json!({"type": "boolean"}),
+        Kind::JSON | Kind::Any => json!({"x-kubernetes-preserve-unknown-fields": true}),
+    };
+    if let Some(docs) = &descriptor.docs {
+        if !docs.is_empty() && schema.get("description").is_none() {
+            schema["description"] = json!(docs.join("\n").trim());
+        }
+    }
+    Ok(schema)
+}
+
+/// Build the structural schema for the fields of a struct
+fn object<'a, Metadata: Default + KubernetesMetadata>(children: &'a [Entry<Metadata>], path: &str, stack: &mut Vec<&'a str>) -> Result<Value, CrdError> {
+    let mut properties = Map::new();
+    let mut required = vec![];
+    for child in children {
+        if child.skip_serializing {
+            continue
+        }
+        let path = format!("{path}.{}", child.label);
+        let mut property = schema(&child.type_info, &path, stack)?;
+        if let Some(docs) = &child.docs {
+            if !docs.is_empty() {
+                property["description"] = json!(docs.join("\n").trim());
+            }
+        }
+        list_type(&mut property, &child.metadata, &child.type_info, &path)?;
+        if !child.has_default && !matches!(unalias(&child.type_info).kind, Kind::Option(_)) {
+            required.push(child.label.as_ref());
+        }
+        properties.insert(child.label.to_string(), property);
+    }
+
+    let mut schema = json!({"type": "object", "properties": properties});
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    Ok(schema)
+}
+
+/// Apply the list type settings of a field
+fn list_type<Metadata: Default + KubernetesMetadata>(schema: &mut Value, metadata: &Metadata, descriptor: &Descriptor<Metadata>, path: &str) -> Result<(), CrdError> {
+    let list_type = metadata.list_type();
+    let keys = metadata.list_map_keys();
+    if list_type.is_none() && keys.is_empty() {
+        return Ok(())
+    }
+    let invalid = |reason: String| CrdError::InvalidListType { path: path.to_owned(), reason };
+
+    let Kind::Sequence(item) = &unwrap(descriptor).kind else {
+        return Err(invalid("list types can only be set on lists".to_owned()))
+    };
+    let list_type = list_type.unwrap_or_else(|| "map".to_owned());
+    match list_type.as_str() {
+        "atomic" | "set" if keys.is_empty() => {},
+        "atomic" | "set" => return Err(invalid(format!("list map keys can't be used with the {list_type} list type"))),
+        "map" => {
+            if keys.is_empty() {
+                return Err(invalid("the map list type needs list map keys".to_owned()))
+            }
+            let Kind::Struct { children, .. } = &unwrap(item).kind else {
+                return Err(invalid("the map list type needs struct items".to_owned()))
+            };
+            for key in &keys {
+                let Some(child) = children.iter().find(|child| child.label == *key) else {
+                    return Err(invalid(format!("list map key {key} isn't a field of the items")))
+                };
+                if !child.has_default && matches!(unalias(&child.type_info).kind, Kind::Option(_)) {
+                    return Err(invalid(format!("list map key {key} must be required or have a default")))
+                }
+            }
+        },
+        other => return Err(invalid(format!("unknown list type {other}"))),
+    }
+
+    schema["x-kubernetes-list-type"] = json!(list_type);
+    if !keys.is_empty() {
+        schema["x-kubernetes-list-map-keys"] = json!(keys);
+    }
+    Ok(())
+}
+
+/// Write a JSON value as a block style YAML document
+fn yaml(value: &Value) -> String {
+    let mut output = String::new();
+    write_yaml(&mut output, value, 0);
+    output
+}
+
+/// Write a value nested under a key or list item at the given indentation
+fn write_yaml(output: &mut String, value: &Value, indent: usize) {
+    let padding = " ".repeat(indent);
+    match value {
+        Value::Object(items) if !items.is_empty() => {
+            for (key, value) in items {
+                output.push_str(&padding);
+                output.push_str(&yaml_scalar(key));
+                write_nested(output, value, indent);
+            }
+        },
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                output.push_str(&padding);
+                output.push('-');
+                match item {
+                    Value::Object(fields) if !fields.is_empty() => {
+                        // the first field shares a line with the dash
+                        let mut nested = String::new();
+                        write_yaml(&mut nested, item, indent + 2);
+                        output.push(' ');
+                        output.push_str(&nested[indent + 2..]);
+                    },
+                    Value::Array(values) if !values.is_empty() => {
+                        output.push('\n');
+                        write_yaml(output, item, indent + 2);
+                    },
+                    scalar => {
+                        output.push(' ');
+                        output.push_str(&inline_yaml(scalar));
+                        output.push('\n');
+                    },
+                }
+            }
+        },
+        scalar => {
+            output.push_str(&padding);
+            output.push_str(&inline_yaml(scalar));
+            output.push('\n');
+        },
+    }
+}
+
+/// Write the value after a mapping key
+fn write_nested(output: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(items) if !items.is_empty() => {
+            output.push_str(":\n");
+            write_yaml(output, value, indent + 2);
+        },
+        Value::Array(items) if !items.is_empty() => {
+            output.push_str(":\n");
+            write_yaml(output, value, indent + 2);
+        },
+        scalar => {
+            output.push_str(": ");
+            output.push_str(&inline_yaml(scalar));
+            output.push('\n');
+        },
+    }
+}
+
+/// Write a scalar or empty collection on one line
+fn inline_yaml(value: &Value) -> String {
+    match value {
+        Value::String(text) => yaml_scalar(text),
+        Value::Object(_) => "{}".to_owned(),
+        Value::Array(_) => "[]".to_owned(),
+        value => value.to_string(),
+    }
+}
+
+/// Write a string, quoting it when it would be read as something else.
+///
+/// Strings starting with a digit or sign are always quoted, which covers the numbers and dates of
+/// both YAML 1.1 and 1.2, the remaining boolean, null and special float words are checked by name.
+fn yaml_scalar(text: &str) -> String {
+    let mut chars = text.chars();
+    let leading = match chars.next() {
+        Some('.') => chars.next().is_some_and(|c| c.is_ascii_alphabetic()),
+        Some(c) => c.is_ascii_alphabetic() || c == '_' || c == '/',
+        None => false,
+    };
+    let plain = leading
+        && !text.ends_with(' ')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || "_./- ".contains(c))
+        && !["true", "false", "yes", "no", "on", "off", "null", "y", "n", ".inf", ".nan"].contains(&text.to_lowercase().as_str());
+    if plain { text.to_owned() } else { Value::String(text.to_owned()).to_string() }
+}
@@ -14,13 +14,61 @@
 
 pub use struct_metadata_derive::{Described, MetadataKind};
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 
+mod compare;
+mod diff;
+mod display;
+mod fingerprint;
+mod flatten;
+mod map;
+mod named;
+#[cfg(feature = "serde_json")]
+mod validate;
+
+pub mod diagram;
+pub mod graphql;
+pub mod markdown;
+pub mod protobuf;
+pub mod python;
+pub mod rust;
+pub mod sql;
+pub mod typescript;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "avro")]
+pub mod avro;
+#[cfg(feature = "elasticsearch")]
+pub mod elasticsearch;
+#[cfg(feature = "json_schema")]
+pub mod json_schema;
+#[cfg(feature = "kubernetes")]
+pub mod kubernetes;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+#[cfg(feature = "template")]
+pub mod template;
+
+pub use compare::{CompareOptions, Difference};
+pub use diff::{Change, ChangeKind, Compatibility, SchemaDiff};
+pub use display::{Pretty, PrettyMetadata};
+pub use fingerprint::{FingerprintMetadata, FingerprintOptions};
+pub use flatten::{FlatField, MappingPolicy, MergeMetadata};
+#[cfg(feature = "serde_json")]
+pub use validate::{Validator, Violation, ViolationKind};
+
 /// Information about a type along with its metadata and doc-strings.
+///
+/// Names, labels and docs borrow static strings when derived, and own them when built at
+/// runtime such as by importing a schema.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Descriptor<Metadata: Default> {
     /// Docstring for the type
-    pub docs: Option<Vec<&'static str>>,
+    pub docs: Option<Vec<Cow<'static, str>>>,
     /// Metadata for the type
     pub metadata: Metadata,
     /// Details about the type
@@ -96,21 +144,21 @@ pub enum Kind<Metadata: Default> {
     /// The type is a struct
     Struct {
         /// Name given to the struct in its declaration
-        name: &'static str,
+        name: Cow<'static, str>,
         /// List of fields within this struct
         children: Vec<Entry<Metadata>>,
     },
     /// A struct wrapping a single anonymous field
     Aliased {
         /// Name given to the struct in its declaration
-        name: &'static str,
+        name: Cow<'static, str>,
         /// The type this alias struct wraps
         kind: Box<Descriptor<Metadata>>
     },
     /// A simple no-field enum type
     Enum {
         /// Name given to the enum in its declaration
-        name: &'static str,
+        name: Cow<'static, str>,
         /// Information about each variant value within this enum
         variants: Vec<Variant<Metadata>>,
     },
@@ -158,7 +206,7 @@ pub enum Kind<Metadata: Default> {
 
 impl<Metadata: MetadataKind> Kind<Metadata> {
     /// Fetch the name of the type
-    pub fn name(&self) -> &'static str {
+    pub fn name(&self) -> &str {
         match self {
             Kind::Struct { name, .. } => name,
             Kind::Aliased { name, .. } => name,
@@ -190,7 +238,7 @@ impl<Metadata: MetadataKind> Kind<Metadata> {
     /// 
     /// Any structs in the flattened_children list will have their fields added to this
     /// new struct as if they were members of it. (this corresponds to the 'flatten' parameter in serde)
-    pub fn new_struct(name: &'static str, mut children: Vec<Entry<Metadata>>, flattened_children: &mut [Descriptor<Metadata>], flattened_metadata: &mut [Metadata]) -> Self {
+    pub fn new_struct(name: impl Into<Cow<'static, str>>, mut children: Vec<Entry<Metadata>>, flattened_children: &mut [Descriptor<Metadata>], flattened_metadata: &mut [Metadata]) -> Self {
         for (child, meta) in flattened_children.iter_mut().zip(flattened_metadata.iter_mut()) {
             if let Kind::Struct { children: flattening, .. } = &mut child.kind {
                 for child in flattening.iter_mut() {
@@ -202,7 +250,7 @@ impl<Metadata: MetadataKind> Kind<Metadata> {
             }
         }
 
-        Self::Struct { name, children }
+        Self::Struct { name: name.into(), children }
     }
 }
 
@@ -211,23 +259,26 @@ impl<Metadata: MetadataKind> Kind<Metadata> {
 pub struct Variant<Metadata: Default> {
     /// String value used to describe the variant.
     /// The DescribedEnumString derive can be used to build this label using the to_string method
-    pub label: &'static str,
+    pub label: Cow<'static, str>,
     /// doc strings describing this variant
-    pub docs: Option<Vec<&'static str>>,
+    pub docs: Option<Vec<Cow<'static, str>>>,
     /// metadata describing this variant
     pub metadata: Metadata,
     /// List of names this field may be known as
-    pub aliases: &'static [&'static str]
+    pub aliases: Vec<Cow<'static, str>>
 }
 
 /// Struct describing a struct field
+///
+/// New fields may be added in minor releases, build entries with [`Entry::new`].
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct Entry<Metadata: Default> {
     /// Label of the field in question
     /// This respects serde's rename attribute
-    pub label: &'static str,
+    pub label: Cow<'static, str>,
     /// doc string describing this field
-    pub docs: Option<Vec<&'static str>>,
+    pub docs: Option<Vec<Cow<'static, str>>>,
     /// metadata describing this field
     pub metadata: Metadata,
     /// Type of this field
@@ -235,12 +286,69 @@ pub struct Entry<Metadata: Default> {
     /// Wether this field has a default defined
     pub has_default: bool,
     /// List of names this field may be known as
-    pub aliases: &'static [&'static str]
+    pub aliases: Vec<Cow<'static, str>>,
+    /// Wether this field is left out when serializing
+    pub skip_serializing: bool,
+    /// Wether this field is ignored when deserializing
+    pub skip_deserializing: bool,
+}
+
+impl<Metadata: Default> Entry<Metadata> {
+    /// Describe a field with the given label and type, without docs, metadata, default or aliases
+    pub fn new(label: impl Into<Cow<'static, str>>, type_info: Descriptor<Metadata>) -> Self {
+        Entry {
+            label: label.into(),
+            docs: None,
+            metadata: Default::default(),
+            type_info,
+            has_default: false,
+            aliases: vec![],
+            skip_serializing: false,
+            skip_deserializing: false,
+        }
+    }
+
+    /// Set the doc strings describing this field
+    pub fn with_docs(mut self, docs: Option<Vec<Cow<'static, str>>>) -> Self {
+        self.docs = docs;
+        self
+    }
+
+    /// Set the metadata describing this field
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Set whether this field has a default defined
+    pub fn with_default(mut self, has_default: bool) -> Self {
+        self.has_default = has_default;
+        self
+    }
+
+    /// Set the names this field may be known as, including its label
+    pub fn with_aliases(mut self, aliases: impl IntoIterator<Item = impl Into<Cow<'static, str>>>) -> Self {
+        self.aliases = aliases.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set whether this field is left out when serializing
+    pub fn with_skip_serializing(mut self, skip: bool) -> Self {
+        self.skip_serializing = skip;
+        self
+    }
+
+    /// Set whether this field is ignored when deserializing
+    pub fn with_skip_deserializing(mut self, skip: bool) -> Self {
+        self.skip_deserializing = skip;
+        self
+    }
 }
 
 impl<T: PartialEq + Default> PartialEq for Entry<T> {
     fn eq(&self, other: &Self) -> bool {
         self.label == other.label && self.docs == other.docs && self.metadata == other.metadata && self.type_info == other.type_info && self.has_default == other.has_default
+            && self.skip_serializing == other.skip_serializing && self.skip_deserializing == other.skip_deserializing
     }
 }
 
@@ -0,0 +1,93 @@
+//! Conversion of descriptors between metadata types.
+
+use crate::{Descriptor, Entry, Kind, Variant};
+
+/// Conversion function applied to each metadata value along with its path
+type Converter<'a, Metadata, N, E> = dyn FnMut(&[&str], Metadata) -> Result<N, E> + 'a;
+
+impl<Metadata: Default> Descriptor<Metadata> {
+    /// Rebuild this descriptor with every metadata value converted by the given function.
+    ///
+    /// The function is called with the labels of the fields (or enum variant) leading to the
+    /// metadata being converted, an empty path refers to the outermost type.
+    pub fn map_metadata<N: Default>(self, mut convert: impl FnMut(&[&str], Metadata) -> N) -> Descriptor<N> {
+        let result: Result<_, core::convert::Infallible> = self.try_map_metadata(|path, metadata| Ok(convert(path, metadata)));
+        match result {
+            Ok(descriptor) => descriptor,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Rebuild this descriptor with every metadata value converted by a fallible function.
+    ///
+    /// Conversion stops at the first error, which is returned unchanged.
+    pub fn try_map_metadata<N: Default, E>(self, mut convert: impl FnMut(&[&str], Metadata) -> Result<N, E>) -> Result<Descriptor<N>, E> {
+        self.try_map_into(&[], &mut convert)
+    }
+
+    /// Recursive helper for `try_map_metadata`
+    fn try_map_into<N: Default, E>(self, path: &[&str], convert: &mut Converter<'_, Metadata, N, E>) -> Result<Descriptor<N>, E> {
+        let metadata = convert(path, self.metadata)?;
+        let kind = match self.kind {
+            Kind::Struct { name, children } => {
+                let mut entries = Vec::with_capacity(children.len());
+                for child in children {
+                    let label = child.label.clone();
+                    entries.push(child.try_map_into(&[path, &[&*label]].concat(), convert)?);
+                }
+                Kind::Struct { name, children: entries }
+            },
+            Kind::Aliased { name, kind } => Kind::Aliased { name, kind: Box::new(kind.try_map_into(path, convert)?) },
+            Kind::Enum { name, variants } => {
+                let mut converted = Vec::with_capacity(variants.len());
+                for variant in variants {
+                    let metadata = convert(&[path, &[&*variant.label]].concat(), variant.metadata);
+                    converted.push(Variant {
+                        label: variant.label,
+                        docs: variant.docs,
+                        metadata: metadata?,
+                        aliases: variant.aliases,
+                    });
+                }
+                Kind::Enum { name, variants: converted }
+            },
+            Kind::Sequence(kind) => Kind::Sequence(Box::new(kind.try_map_into(path, convert)?)),
+            Kind::Option(kind) => Kind::Option(Box::new(kind.try_map_into(path, convert)?)),
+            Kind::Mapping(key, value) => Kind::Mapping(Box::new(key.try_map_into(path, convert)?), Box::new(value.try_map_into(path, convert)?)),
+            Kind::DateTime => Kind::DateTime,
+            Kind::String => Kind::String,
+            Kind::U128 => Kind::U128,
+            Kind::I128 => Kind::I128,
+            Kind::U64 => Kind::U64,
+            Kind::I64 => Kind::I64,
+            Kind::U32 => Kind::U32,
+            Kind::I32 => Kind::I32,
+            Kind::U16 => Kind::U16,
+            Kind::I16 => Kind::I16,
+            Kind::U8 => Kind::U8,
+            Kind::I8 => Kind::I8,
+            Kind::F64 => Kind::F64,
+            Kind::F32 => Kind::F32,
+            Kind::Bool => Kind::Bool,
+            Kind::JSON => Kind::JSON,
+            Kind::Any => Kind::Any,
+        };
+        Ok(Descriptor { docs: self.docs, metadata, kind })
+    }
+}
+
+impl<Metadata: Default> Entry<Metadata> {
+    /// Convert the metadata of this field and its type, the path should already include this field's label
+    fn try_map_into<N: Default, E>(self, path: &[&str], convert: &mut Converter<'_, Metadata, N, E>) -> Result<Entry<N>, E> {
+        Ok(Entry {
+            label: self.label,
+            docs: self.docs,
+            metadata: convert(path, self.metadata)?,
+            type_info: self.type_info.try_map_into(path, convert)?,
+            has_default: self.has_default,
+            aliases: self.aliases,
+            skip_serializing: self.skip_serializing,
+            skip_deserializing: self.skip_deserializing,
+        })
+    }
+}
@@ -0,0 +1,169 @@
+//! Generate Markdown reference documentation from descriptors.
+//!
+//! Every struct, enum and aliased type gets a section, sorted by name, with the types used by
+//! its fields linked to their own sections. Metadata can be shown as extra table columns by
+//! registering a function that formats it.
+
+use std::fmt::Write;
+
+use crate::named::{doc_lines, named_types};
+use crate::{Descriptor, Entry, Kind};
+
+/// Function used to render a metadata column
+type Column<'a, Metadata> = dyn Fn(&Metadata) -> Option<String> + 'a;
+
+/// Configurable Markdown documentation generator
+pub struct Markdown<'a, Metadata> {
+    /// Title written at the top of the document
+    title: Option<String>,
+    /// Heading level used for each type section
+    level: usize,
+    /// Extra columns in field tables built from field metadata
+    columns: Vec<(String, Box<Column<'a, Metadata>>)>,
+}
+
+impl<Metadata> Default for Markdown<'_, Metadata> {
+    fn default() -> Self {
+        Self {
+            title: None,
+            level: 2,
+            columns: vec![],
+        }
+    }
+}
+
+impl<'a, Metadata: Default> Markdown<'a, Metadata> {
+    /// Create a generator with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Write a top level heading with the given title, type sections are placed below it
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    /// Set the heading level used for type sections (2 by default)
+    pub fn heading_level(mut self, level: usize) -> Self {
+        self.level = level.clamp(1, 6);
+        self
+    }
+
+    /// Add a column to field tables, the function renders the field metadata into a cell
+    pub fn column(mut self, header: &str, render: impl Fn(&Metadata) -> Option<String> + 'a) -> Self {
+        self.columns.push((header.to_owned(), Box::new(render)));
+        self
+    }
+
+    /// Generate a document with a section for every named type used by the given descriptors
+    pub fn generate<'b>(&self, roots: impl IntoIterator<Item = &'b Descriptor<Metadata>>) -> String where Metadata: 'b {
+        let mut output = String::new();
+        if let Some(title) = &self.title {
+            let _ = writeln!(output, "# {title}");
+        }
+        let heading = "#".repeat(self.level);
+
+        for (name, descriptor) in named_types(roots) {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            let _ = writeln!(output, "{heading} {name}\n");
+            let docs = doc_lines(&descriptor.docs);
+            if !docs.is_empty() {
+                let _ = writeln!(output, "{}\n", docs.join("\n"));
+            }
+
+            match &descriptor.kind {
+                Kind::Struct { children, .. } => self.write_table(&mut output, children),
+                Kind::Enum { variants, .. } => {
+                    output.push_str("Variants:\n\n");
+                    for variant in variants {
+                        let _ = write!(output, "- `{}`", variant.label);
+                        let aliases: Vec<_> = variant.aliases.iter().filter(|alias| **alias != variant.label).map(|alias| format!("`{alias}`")).collect();
+                        if !aliases.is_empty() {
+                            let _ = write!(output, " (aliases: {})", aliases.join(", "));
+                        }
+                        let docs = doc_lines(&variant.docs);
+                        if !docs.is_empty() {
+                            let _ = write!(output, ": {}", inline(&docs));
+                        }
+                        output.push('\n');
+                    }
+                },
+                Kind::Aliased { kind, .. } => {
+                    let _ = writeln!(output, "Alias of {}.", type_link(kind));
+                },
+                _ => {}
+            }
+        }
+        output
+    }
+
+    /// Write the field table for a struct
+    fn write_table(&self, output: &mut String, children: &[Entry<Metadata>]) {
+        let mut headers = vec!["Field", "Type", "Required", "Aliases", "Description"];
+        headers.extend(self.columns.iter().map(|(header, _)| header.as_str()));
+        let _ = writeln!(output, "| {} |", headers.join(" | "));
+        let _ = writeln!(output, "|{}", " --- |".repeat(headers.len()));
+
+        for child in children {
+            if child.skip_serializing && child.skip_deserializing {
+                continue
+            }
+            let required = if matches!(child.type_info.kind, Kind::Option(_)) {
+                "optional"
+            } else if child.has_default || child.skip_deserializing {
+                "default"
+            } else {
+                "required"
+            };
+            let aliases: Vec<_> = child.aliases.iter().filter(|alias| **alias != child.label).map(|alias| format!("`{alias}`")).collect();
+            let mut cells = vec![
+                format!("`{}`", child.label),
+                type_link(&child.type_info),
+                required.to_owned(),
+                aliases.join(", "),
+                inline(&doc_lines(&child.docs)),
+            ];
+            for (_, render) in &self.columns {
+                cells.push(render(&child.metadata).map(|cell| escape(&cell)).unwrap_or_default());
+            }
+            let _ = writeln!(output, "| {} |", cells.join(" | "));
+        }
+    }
+}
+
+/// Render a type with named types linked to their sections
+fn type_link<Metadata: Default>(descriptor: &Descriptor<Metadata>) -> String {
+    match &descriptor.kind {
+        Kind::Struct { name, .. } |
+        Kind::Aliased { name, .. } |
+        Kind::Enum { name, .. } => format!("[{name}](#{})", name.to_lowercase()),
+        Kind::Sequence(kind) => format!("Vec&lt;{}&gt;", type_link(kind)),
+        Kind::Option(kind) => format!("Option&lt;{}&gt;", type_link(kind)),
+        Kind::Mapping(key, value) => format!("HashMap&lt;{}, {}&gt;", type_link(key), type_link(value)),
+        kind => kind.type_expression(),
+    }
+}
+
+/// Join doc lines into a single table cell, blank lines become line breaks
+fn inline(lines: &[&str]) -> String {
+    let mut output = String::new();
+    for line in lines {
+        if line.is_empty() {
+            output.push_str("<br>");
+        } else {
+            if !output.is_empty() && !output.ends_with("<br>") {
+                output.push(' ');
+            }
+            output.push_str(&escape(line));
+        }
+    }
+    output
+}
+
+/// Escape text placed inside a table cell
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
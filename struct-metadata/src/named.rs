@@ -0,0 +1,107 @@
+//! Helpers shared by the generators, for finding the named types used by a descriptor and for
+//! building names, paths and literals.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use crate::{Descriptor, Kind};
+
+/// Collect every struct, enum and aliased type reachable from the given roots.
+///
+/// Types are keyed by name so that generated output has a stable order, when two types
+/// share a name the first one found is kept.
+pub(crate) fn named_types<'a, Metadata: Default + 'a>(roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> BTreeMap<&'a str, &'a Descriptor<Metadata>> {
+    let mut output = BTreeMap::new();
+    for root in roots {
+        collect(root, &mut output);
+    }
+    output
+}
+
+/// Recursive helper for `named_types`
+fn collect<'a, Metadata: Default>(descriptor: &'a Descriptor<Metadata>, output: &mut BTreeMap<&'a str, &'a Descriptor<Metadata>>) {
+    match &descriptor.kind {
+        Kind::Struct { name, children } => {
+            if output.contains_key(name.as_ref()) {
+                return
+            }
+            output.insert(name.as_ref(), descriptor);
+            for child in children {
+                collect(&child.type_info, output);
+            }
+        },
+        Kind::Aliased { name, kind } => {
+            if output.contains_key(name.as_ref()) {
+                return
+            }
+            output.insert(name.as_ref(), descriptor);
+            collect(kind, output);
+        },
+        Kind::Enum { name, .. } => {
+            output.entry(name.as_ref()).or_insert(descriptor);
+        },
+        Kind::Sequence(kind) | Kind::Option(kind) => collect(kind, output),
+        Kind::Mapping(key, value) => {
+            collect(key, output);
+            collect(value, output);
+        },
+        _ => {}
+    }
+}
+
+/// Split doc strings into lines, dropping trailing empty lines
+pub(crate) fn doc_lines<'a>(docs: &'a Option<Vec<Cow<'static, str>>>) -> Vec<&'a str> {
+    let mut lines: Vec<&str> = docs.iter().flatten().map(AsRef::as_ref).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Look through aliased types to the type they wrap
+pub(crate) fn unalias<Metadata: Default>(mut descriptor: &Descriptor<Metadata>) -> &Descriptor<Metadata> {
+    while let Kind::Aliased { kind, .. } = &descriptor.kind {
+        descriptor = kind;
+    }
+    descriptor
+}
+
+/// Remove the quotes the derive macro leaves on string literal metadata values
+pub(crate) fn unquote(value: &str) -> &str {
+    value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value)
+}
+
+/// Quote a string with double quotes and backslash escapes, as used by most languages
+pub(crate) fn string_literal(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// Build an identifier from a label by replacing anything other than letters, digits and
+/// underscores, `prefix` is added when the label doesn't start with a letter or the prefix
+pub(crate) fn field_name(label: &str, prefix: char) -> String {
+    let mut name: String = label.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' }).collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == prefix) {
+        name.insert(0, prefix);
+    }
+    name
+}
+
+/// Build an upper snake case name from a label, as used for constants and enum values
+pub(crate) fn constant_name(label: &str) -> String {
+    let mut name = String::new();
+    let mut previous_lower = false;
+    for c in label.chars() {
+        if c.is_ascii_uppercase() && previous_lower {
+            name.push('_');
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        name.push(if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' });
+    }
+    name
+}
+
+/// Extend a dotted path, an empty path is the root
+#[cfg(feature = "serde_json")]
+pub(crate) fn child_path(path: &str, name: &str) -> String {
+    if path.is_empty() { name.to_owned() } else { format!("{path}.{name}") }
+}
@@ -0,0 +1,224 @@
+//! Build OpenAPI 3.1 documents from described request and response types.
+//!
+//! Schemas are generated with the [`JsonSchema`] exporter, OpenAPI 3.1 uses JSON Schema
+//! draft 2020-12 directly, so nullable values are written as a union with `null`. Fields that
+//! serde skips when deserializing are marked `readOnly` and fields skipped when serializing
+//! are marked `writeOnly`.
+
+use serde_json::{json, Map, Value};
+
+use crate::json_schema::JsonSchema;
+use crate::{Described, Descriptor};
+
+/// Version of the OpenAPI specification being produced
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// HTTP methods an operation can be declared for
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Method {
+    /// HTTP GET
+    Get,
+    /// HTTP PUT
+    Put,
+    /// HTTP POST
+    Post,
+    /// HTTP DELETE
+    Delete,
+    /// HTTP PATCH
+    Patch,
+    /// HTTP HEAD
+    Head,
+    /// HTTP OPTIONS
+    Options,
+}
+
+impl Method {
+    /// Name of the method as used for keys in an OpenAPI path item
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "get",
+            Method::Put => "put",
+            Method::Post => "post",
+            Method::Delete => "delete",
+            Method::Patch => "patch",
+            Method::Head => "head",
+            Method::Options => "options",
+        }
+    }
+}
+
+/// An HTTP operation referencing described request and response types
+#[derive(Debug, Clone)]
+pub struct Operation<Metadata: Default> {
+    /// Method this operation responds to
+    method: Method,
+    /// URL path template of this operation
+    path: String,
+    /// Unique identifier of the operation
+    operation_id: Option<String>,
+    /// Short summary of the operation
+    summary: Option<String>,
+    /// Type of the JSON request body if there is one
+    request: Option<Descriptor<Metadata>>,
+    /// Status codes paired with the type of the JSON response body
+    responses: Vec<(u16, Option<Descriptor<Metadata>>)>,
+}
+
+impl<Metadata: Default> Operation<Metadata> {
+    /// Declare an operation on the given path
+    pub fn new(method: Method, path: &str) -> Self {
+        Self {
+            method,
+            path: path.to_owned(),
+            operation_id: None,
+            summary: None,
+            request: None,
+            responses: vec![],
+        }
+    }
+
+    /// Set the unique identifier of this operation
+    pub fn operation_id(mut self, id: &str) -> Self {
+        self.operation_id = Some(id.to_owned());
+        self
+    }
+
+    /// Set a short summary of this operation
+    pub fn summary(mut self, summary: &str) -> Self {
+        self.summary = Some(summary.to_owned());
+        self
+    }
+
+    /// Accept a JSON request body of the given type
+    pub fn request<T: Described<Metadata>>(self) -> Self {
+        self.request_descriptor(T::metadata())
+    }
+
+    /// Accept a JSON request body described by the given descriptor
+    pub fn request_descriptor(mut self, descriptor: Descriptor<Metadata>) -> Self {
+        self.request = Some(descriptor);
+        self
+    }
+
+    /// Respond with a JSON body of the given type for a status code
+    pub fn response<T: Described<Metadata>>(self, status: u16) -> Self {
+        self.response_descriptor(status, T::metadata())
+    }
+
+    /// Respond with a JSON body described by the given descriptor for a status code
+    pub fn response_descriptor(mut self, status: u16, descriptor: Descriptor<Metadata>) -> Self {
+        self.responses.push((status, Some(descriptor)));
+        self
+    }
+
+    /// Respond without a body for a status code
+    pub fn empty_response(mut self, status: u16) -> Self {
+        self.responses.push((status, None));
+        self
+    }
+}
+
+/// Builder for an OpenAPI document from a set of registered types and operations
+pub struct OpenApi<'a, Metadata: Default> {
+    /// Title of the API
+    title: String,
+    /// Version of the API
+    version: String,
+    /// Generator used to build component schemas
+    generator: JsonSchema<'a, Metadata>,
+    /// Types registered directly as components
+    types: Vec<Descriptor<Metadata>>,
+    /// Operations to include in the paths section
+    operations: Vec<Operation<Metadata>>,
+}
+
+impl<'a, Metadata: Default> OpenApi<'a, Metadata> {
+    /// Start a document for an API with the given title and version
+    pub fn new(title: &str, version: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+            version: version.to_owned(),
+            generator: JsonSchema::new().reference_prefix("#/components/schemas/"),
+            types: vec![],
+            operations: vec![],
+        }
+    }
+
+    /// Add a function that can insert extra keywords into schemas based on metadata
+    pub fn with_hook(mut self, hook: impl Fn(&Metadata, &mut Map<String, Value>) + 'a) -> Self {
+        self.generator = self.generator.with_hook(hook);
+        self
+    }
+
+    /// Add a type to the component schemas.
+    ///
+    /// Only structs, enums and aliased types are given a component entry, any named types
+    /// they use are registered along with them.
+    pub fn register<T: Described<Metadata>>(self) -> Self {
+        self.register_descriptor(T::metadata())
+    }
+
+    /// Add the type described by a descriptor to the component schemas
+    pub fn register_descriptor(mut self, descriptor: Descriptor<Metadata>) -> Self {
+        self.types.push(descriptor);
+        self
+    }
+
+    /// Add an operation, its request and response types are registered automatically
+    pub fn operation(mut self, operation: Operation<Metadata>) -> Self {
+        self.operations.push(operation);
+        self
+    }
+
+    /// Build the OpenAPI document
+    pub fn build(&self) -> Value {
+        let mut schemas = Map::new();
+        for descriptor in &self.types {
+            self.generator.schema(descriptor, &mut schemas);
+        }
+
+        let mut paths = Map::new();
+        for operation in &self.operations {
+            let mut output = Map::new();
+            if let Some(id) = &operation.operation_id {
+                output.insert("operationId".to_owned(), json!(id));
+            }
+            if let Some(summary) = &operation.summary {
+                output.insert("summary".to_owned(), json!(summary));
+            }
+            if let Some(request) = &operation.request {
+                let mut body = json!({
+                    "required": true,
+                    "content": {"application/json": {"schema": self.generator.schema(request, &mut schemas)}},
+                });
+                if let Some(docs) = &request.docs {
+                    body["description"] = json!(docs.join("\n"));
+                }
+                output.insert("requestBody".to_owned(), body);
+            }
+
+            let mut responses = Map::new();
+            for (status, response) in &operation.responses {
+                let value = match response {
+                    Some(response) => json!({
+                        "description": response.docs.as_ref().map(|docs| docs.join("\n")).unwrap_or_else(|| "Response".to_owned()),
+                        "content": {"application/json": {"schema": self.generator.schema(response, &mut schemas)}},
+                    }),
+                    None => json!({"description": "No content"}),
+                };
+                responses.insert(status.to_string(), value);
+            }
+            output.insert("responses".to_owned(), Value::Object(responses));
+
+            let item = paths.entry(operation.path.clone()).or_insert_with(|| json!({}));
+            item[operation.method.as_str()] = Value::Object(output);
+        }
+
+        json!({
+            "openapi": OPENAPI_VERSION,
+            "info": {"title": self.title, "version": self.version},
+            "paths": paths,
+            "components": {"schemas": schemas},
+        })
+    }
+}
@@ -0,0 +1,264 @@
+//! Generate proto3 schema files from descriptors.
+//!
+//! Structs become messages and enums become enums with a zero `UNSPECIFIED` value. Field
+//! numbers are assigned in declaration order, numbers pinned through [`ProtobufMetadata`] are
+//! kept as given and skipped when numbering the other fields, so pinning the number of every
+//! existing field keeps them stable as new fields are added.
+
+use core::fmt;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::named::{constant_name, doc_lines, field_name, named_types, unalias, unquote};
+use crate::{Descriptor, Kind};
+
+/// Largest field number protobuf allows
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+/// Field numbers reserved for the protobuf implementation
+const RESERVED_NUMBERS: std::ops::RangeInclusive<u32> = 19000..=19999;
+
+/// Metadata settings that control how a field is numbered
+pub trait ProtobufMetadata {
+    /// Fixed field number for this field (or enum value)
+    fn field_number(&self) -> Option<u32> { None }
+}
+
+/// Reads the `field_number` key
+impl<K: AsRef<str>, V: AsRef<str>> ProtobufMetadata for HashMap<K, V> {
+    fn field_number(&self) -> Option<u32> {
+        self.iter().find(|(key, _)| key.as_ref() == "field_number").and_then(|(_, value)| unquote(value.as_ref()).parse().ok())
+    }
+}
+
+/// Reasons a descriptor can't be written as a proto file
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ProtobufError {
+    /// A type that has no protobuf equivalent, like a list of lists
+    Unsupported {
+        /// Dotted path to the field
+        path: String,
+        /// Why the type can't be represented
+        reason: &'static str,
+    },
+    /// Two fields of a message pinned to the same number
+    DuplicateNumber {
+        /// Dotted path to the second field using the number
+        path: String,
+        /// The repeated number
+        number: u32,
+    },
+    /// A pinned number outside of the range protobuf allows
+    InvalidNumber {
+        /// Dotted path to the field
+        path: String,
+        /// The rejected number
+        number: u32,
+    },
+}
+
+impl fmt::Display for ProtobufError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtobufError::Unsupported { path, reason } => write!(f, "{path}: {reason}"),
+            ProtobufError::DuplicateNumber { path, number } => write!(f, "{path}: field number {number} is already used"),
+            ProtobufError::InvalidNumber { path, number } => write!(f, "{path}: {number} is not a valid field number"),
+        }
+    }
+}
+
+impl std::error::Error for ProtobufError {}
+
+/// Configurable proto3 schema generator
+#[derive(Debug, Clone, Default)]
+pub struct Protobuf {
+    /// Package declared at the top of the file
+    package: Option<String>,
+}
+
+/// Well known types imported by the generated file
+#[derive(Default)]
+struct Imports {
+    /// Is `google.protobuf.Timestamp` used
+    timestamp: bool,
+    /// Is `google.protobuf.Value` used
+    value: bool,
+}
+
+impl Protobuf {
+    /// Create a generator with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the package declared by the generated file
+    pub fn package(mut self, package: &str) -> Self {
+        self.package = Some(package.to_owned());
+        self
+    }
+
+    /// Generate a proto file declaring every struct and enum used by the given descriptors.
+    ///
+    /// Aliased types are written as the type they wrap since protobuf has no type aliases.
+    pub fn generate<'a, Metadata: Default + ProtobufMetadata + 'a>(&self, roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> Result<String, ProtobufError> {
+        let mut imports = Imports::default();
+        let mut body = String::new();
+
+        for (name, descriptor) in named_types(roots) {
+            match &descriptor.kind {
+                Kind::Struct { children, .. } => {
+                    body.push('\n');
+                    write_comment(&mut body, "", &doc_lines(&descriptor.docs));
+                    let _ = writeln!(body, "message {name} {{");
+
+                    let children: Vec<_> = children.iter().filter(|child| !(child.skip_serializing && child.skip_deserializing)).collect();
+                    let pinned: Vec<_> = children.iter().map(|child| (format!("{name}.{}", child.label), child.metadata.field_number())).collect();
+                    let numbers = assign_numbers(&pinned)?;
+
+                    for (child, number) in children.into_iter().zip(numbers) {
+                        let path = format!("{name}.{}", child.label);
+                        let field = field_name(&child.label, 'f');
+                        let declaration = field_type(&child.type_info, &path, &mut imports)?;
+                        let options = if field == child.label { String::new() } else { format!(" [json_name = \"{}\"]", child.label) };
+                        write_comment(&mut body, "    ", &doc_lines(&child.docs));
+                        let _ = writeln!(body, "    {declaration} {field} = {number}{options};");
+                    }
+                    body.push_str("}\n");
+                },
+                Kind::Enum { variants, .. } => {
+                    body.push('\n');
+                    write_comment(&mut body, "", &doc_lines(&descriptor.docs));
+                    let prefix = constant_name(name);
+                    let _ = writeln!(body, "enum {name} {{");
+                    let _ = writeln!(body, "    {prefix}_UNSPECIFIED = 0;");
+
+                    let pinned: Vec<_> = variants.iter().map(|variant| (format!("{name}.{}", variant.label), variant.metadata.field_number())).collect();
+                    let numbers = assign_numbers(&pinned)?;
+                    for (variant, number) in variants.iter().zip(numbers) {
+                        write_comment(&mut body, "    ", &doc_lines(&variant.docs));
+                        let _ = writeln!(body, "    {prefix}_{} = {number};", constant_name(&variant.label));
+                    }
+                    body.push_str("}\n");
+                },
+                _ => {}
+            }
+        }
+
+        let mut output = String::from("syntax = \"proto3\";\n");
+        if let Some(package) = &self.package {
+            let _ = writeln!(output, "\npackage {package};");
+        }
+        if imports.timestamp || imports.value {
+            output.push('\n');
+        }
+        if imports.value {
+            output.push_str("import \"google/protobuf/struct.proto\";\n");
+        }
+        if imports.timestamp {
+            output.push_str("import \"google/protobuf/timestamp.proto\";\n");
+        }
+        output.push_str(&body);
+        Ok(output)
+    }
+}
+
+/// Assign field numbers, keeping pinned numbers and filling in the rest in order
+fn assign_numbers(fields: &[(String, Option<u32>)]) -> Result<Vec<u32>, ProtobufError> {
+    let mut used = BTreeSet::new();
+    for (path, number) in fields {
+        if let Some(number) = *number {
+            if number == 0 || number > MAX_FIELD_NUMBER || RESERVED_NUMBERS.contains(&number) {
+                return Err(ProtobufError::InvalidNumber { path: path.clone(), number })
+            }
+            if !used.insert(number) {
+                return Err(ProtobufError::DuplicateNumber { path: path.clone(), number })
+            }
+        }
+    }
+
+    let mut next = 1;
+    let mut output = vec![];
+    for (_, number) in fields {
+        match number {
+            Some(number) => output.push(*number),
+            None => {
+                while used.contains(&next) || RESERVED_NUMBERS.contains(&next) {
+                    next += 1;
+                }
+                used.insert(next);
+                output.push(next);
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Render the type of a field including any `repeated` or `optional` label
+fn field_type<Metadata: Default>(descriptor: &Descriptor<Metadata>, path: &str, imports: &mut Imports) -> Result<String, ProtobufError> {
+    let unsupported = |reason| Err(ProtobufError::Unsupported { path: path.to_owned(), reason });
+    match &unalias(descriptor).kind {
+        Kind::Sequence(kind) => match &unalias(kind).kind {
+            Kind::Sequence(_) | Kind::Mapping(..) => unsupported("repeated fields can't contain lists or maps"),
+            Kind::Option(_) => unsupported("repeated fields can't contain optional values"),
+            _ => Ok(format!("repeated {}", value_type(kind, path, imports)?)),
+        },
+        Kind::Option(kind) => match &unalias(kind).kind {
+            Kind::Sequence(_) | Kind::Mapping(..) => unsupported("lists and maps can't be optional"),
+            Kind::Option(_) => unsupported("optional fields can't be nested"),
+            _ => Ok(format!("optional {}", value_type(kind, path, imports)?)),
+        },
+        Kind::Mapping(key, value) => {
+            let key = match &unalias(key).kind {
+                Kind::String => "string",
+                Kind::U64 => "uint64",
+                Kind::I64 => "int64",
+                Kind::U32 | Kind::U16 | Kind::U8 => "uint32",
+                Kind::I32 | Kind::I16 | Kind::I8 => "int32",
+                Kind::Bool => "bool",
+                _ => return unsupported("map keys must be strings, integers or booleans"),
+            };
+            match &unalias(value).kind {
+                Kind::Sequence(_) | Kind::Mapping(..) => unsupported("map values can't be lists or maps"),
+                Kind::Option(_) => unsupported("map values can't be optional"),
+                _ => Ok(format!("map<{key}, {}>", value_type(value, path, imports)?)),
+            }
+        },
+        _ => value_type(descriptor, path, imports),
+    }
+}
+
+/// Render the type of a single value
+fn value_type<Metadata: Default>(descriptor: &Descriptor<Metadata>, path: &str, imports: &mut Imports) -> Result<String, ProtobufError> {
+    Ok(match &unalias(descriptor).kind {
+        Kind::Struct { name, .. } | Kind::Enum { name, .. } => name.to_string(),
+        Kind::DateTime => {
+            imports.timestamp = true;
+            "google.protobuf.Timestamp".to_owned()
+        },
+        Kind::JSON | Kind::Any => {
+            imports.value = true;
+            "google.protobuf.Value".to_owned()
+        },
+        // 128 bit integers don't fit any protobuf scalar
+        Kind::String | Kind::U128 | Kind::I128 => "string".to_owned(),
+        Kind::U64 => "uint64".to_owned(),
+        Kind::I64 => "int64".to_owned(),
+        Kind::U32 | Kind::U16 | Kind::U8 => "uint32".to_owned(),
+        Kind::I32 | Kind::I16 | Kind::I8 => "int32".to_owned(),
+        Kind::F64 => "double".to_owned(),
+        Kind::F32 => "float".to_owned(),
+        Kind::Bool => "bool".to_owned(),
+        _ => return Err(ProtobufError::Unsupported { path: path.to_owned(), reason: "lists, maps and optional values can't be nested" }),
+    })
+}
+
+/// Write doc strings as line comments
+fn write_comment(output: &mut String, indent: &str, lines: &[&str]) {
+    for line in lines {
+        if line.is_empty() {
+            let _ = writeln!(output, "{indent}//");
+        } else {
+            let _ = writeln!(output, "{indent}// {line}");
+        }
+    }
+}
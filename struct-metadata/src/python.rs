@@ -0,0 +1,351 @@
+//! Generate Python models from descriptors.
+//!
+//! Structs become pydantic v2 models or plain dataclasses, enums become `Enum` classes or
+//! `Literal` aliases and aliased types become type aliases. Types are written after the types
+//! they depend on so default factories can refer to them.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use crate::named::{constant_name, doc_lines, field_name, named_types, string_literal, unalias};
+use crate::{Descriptor, Entry, Kind};
+
+/// Kind of class generated for structs
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ModelStyle {
+    /// Subclasses of `pydantic.BaseModel`
+    #[default]
+    Pydantic,
+    /// Classes decorated with `dataclasses.dataclass`
+    Dataclass,
+}
+
+/// How enums are represented
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum EnumStyle {
+    /// A `str` based `Enum` class
+    #[default]
+    Enum,
+    /// A `Literal` type alias over the variant labels
+    Literal,
+}
+
+/// Configurable Python source generator
+#[derive(Debug, Clone, Default)]
+pub struct Python {
+    /// Kind of class generated for structs
+    model: ModelStyle,
+    /// How enums are represented
+    enums: EnumStyle,
+}
+
+/// Imports needed by the generated module
+#[derive(Default)]
+struct Imports {
+    /// Names imported from `typing`
+    typing: BTreeSet<&'static str>,
+    /// Is the `datetime` module used
+    datetime: bool,
+    /// Is `enum.Enum` used
+    enumeration: bool,
+    /// Names imported from `pydantic`
+    pydantic: BTreeSet<&'static str>,
+    /// Names imported from `dataclasses`
+    dataclasses: BTreeSet<&'static str>,
+}
+
+impl Python {
+    /// Create a generator with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the kind of class generated for structs
+    pub fn model_style(mut self, style: ModelStyle) -> Self {
+        self.model = style;
+        self
+    }
+
+    /// Set how enums are represented
+    pub fn enum_style(mut self, style: EnumStyle) -> Self {
+        self.enums = style;
+        self
+    }
+
+    /// Generate a python module declaring every named type used by the given descriptors
+    pub fn generate<'a, Metadata: Default + 'a>(&self, roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> String {
+        let types = named_types(roots);
+        let mut imports = Imports::default();
+        let mut body = String::new();
+
+        for descriptor in dependency_order(&types) {
+            body.push_str("\n\n");
+            match &descriptor.kind {
+                Kind::Struct { name, children } => self.write_model(&mut body, &mut imports, name, &descriptor.docs, children),
+                Kind::Enum { name, variants } => {
+                    match self.enums {
+                        EnumStyle::Enum => {
+                            imports.enumeration = true;
+                            let _ = writeln!(body, "class {name}(str, Enum):");
+                            write_docstring(&mut body, "    ", &doc_lines(&descriptor.docs));
+                            for variant in variants {
+                                let _ = writeln!(body, "    {} = {}", member_name(&variant.label), string_literal(&variant.label));
+                            }
+                            if variants.is_empty() {
+                                body.push_str("    pass\n");
+                            }
+                        },
+                        EnumStyle::Literal => {
+                            imports.typing.insert("Literal");
+                            let labels: Vec<_> = variants.iter().map(|variant| string_literal(&variant.label)).collect();
+                            let _ = writeln!(body, "{name} = Literal[{}]", labels.join(", "));
+                            write_docstring(&mut body, "", &doc_lines(&descriptor.docs));
+                        },
+                    }
+                },
+                Kind::Aliased { name, kind } => {
+                    let _ = writeln!(body, "{name} = {}", type_expression(kind, &mut imports));
+                    write_docstring(&mut body, "", &doc_lines(&descriptor.docs));
+                },
+                _ => {}
+            }
+        }
+
+        let mut output = String::new();
+        output.push_str("from __future__ import annotations\n\n");
+        if imports.datetime {
+            output.push_str("import datetime\n");
+        }
+        if !imports.dataclasses.is_empty() {
+            let _ = writeln!(output, "from dataclasses import {}", imports.dataclasses.iter().copied().collect::<Vec<_>>().join(", "));
+        }
+        if imports.enumeration {
+            output.push_str("from enum import Enum\n");
+        }
+        if !imports.typing.is_empty() {
+            let _ = writeln!(output, "from typing import {}", imports.typing.iter().copied().collect::<Vec<_>>().join(", "));
+        }
+        if !imports.pydantic.is_empty() {
+            let _ = writeln!(output, "\nfrom pydantic import {}", imports.pydantic.iter().copied().collect::<Vec<_>>().join(", "));
+        }
+        output.push_str(&body);
+        output
+    }
+
+    /// Write the class for a struct
+    fn write_model<Metadata: Default>(&self, output: &mut String, imports: &mut Imports, name: &str, docs: &Option<Vec<Cow<'static, str>>>, children: &[Entry<Metadata>]) {
+        match self.model {
+            ModelStyle::Pydantic => {
+                imports.pydantic.insert("BaseModel");
+                let _ = writeln!(output, "class {name}(BaseModel):");
+            },
+            ModelStyle::Dataclass => {
+                imports.dataclasses.insert("dataclass");
+                let _ = writeln!(output, "@dataclass(kw_only=True)\nclass {name}:");
+            },
+        }
+        let docs = doc_lines(docs);
+        write_docstring(output, "    ", &docs);
+
+        let children: Vec<_> = children.iter().filter(|child| !(child.skip_serializing && child.skip_deserializing)).collect();
+        if children.is_empty() {
+            if docs.is_empty() {
+                output.push_str("    pass\n");
+            }
+            return
+        }
+        if !docs.is_empty() {
+            output.push('\n');
+        }
+
+        for child in children {
+            let attribute = attribute_name(&child.label);
+            let annotation = type_expression(&child.type_info, imports);
+            let default = default_value(child);
+            let docs = doc_lines(&child.docs);
+
+            match self.model {
+                ModelStyle::Pydantic => {
+                    let mut arguments = vec![];
+                    if let Some(default) = default {
+                        arguments.push(default);
+                    }
+                    if attribute != child.label {
+                        arguments.push(format!("alias={}", string_literal(&child.label)));
+                    }
+                    let extra: Vec<_> = child.aliases.iter().filter(|alias| **alias != child.label).collect();
+                    if !extra.is_empty() {
+                        imports.pydantic.insert("AliasChoices");
+                        let choices: Vec<_> = Some(&child.label).into_iter().chain(extra).map(|alias| string_literal(alias)).collect();
+                        arguments.push(format!("validation_alias=AliasChoices({})", choices.join(", ")));
+                    }
+                    if !docs.is_empty() {
+                        arguments.push(format!("description={}", string_literal(&docs.join("\n"))));
+                    }
+
+                    if arguments.is_empty() {
+                        let _ = writeln!(output, "    {attribute}: {annotation}");
+                    } else {
+                        imports.pydantic.insert("Field");
+                        let _ = writeln!(output, "    {attribute}: {annotation} = Field({})", arguments.join(", "));
+                    }
+                },
+                ModelStyle::Dataclass => {
+                    for line in docs {
+                        let _ = writeln!(output, "    # {line}");
+                    }
+                    match default {
+                        Some(default) => {
+                            imports.dataclasses.insert("field");
+                            let _ = writeln!(output, "    {attribute}: {annotation} = field({default})");
+                        },
+                        None => {
+                            let _ = writeln!(output, "    {attribute}: {annotation}");
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Order types so that each one follows the types it refers to, otherwise by name
+fn dependency_order<'a, Metadata: Default>(types: &BTreeMap<&'a str, &'a Descriptor<Metadata>>) -> Vec<&'a Descriptor<Metadata>> {
+    let mut output = vec![];
+    let mut visited = BTreeSet::new();
+    for name in types.keys() {
+        visit(name, types, &mut visited, &mut output);
+    }
+    output
+}
+
+/// Depth first helper for `dependency_order`
+fn visit<'a, Metadata: Default>(name: &'a str, types: &BTreeMap<&'a str, &'a Descriptor<Metadata>>, visited: &mut BTreeSet<&'a str>, output: &mut Vec<&'a Descriptor<Metadata>>) {
+    if !visited.insert(name) {
+        return
+    }
+    let Some(descriptor) = types.get(name) else { return };
+    let mut dependencies = BTreeSet::new();
+    match &descriptor.kind {
+        Kind::Struct { children, .. } => {
+            for child in children {
+                references(&child.type_info, &mut dependencies);
+            }
+        },
+        Kind::Aliased { kind, .. } => references(kind, &mut dependencies),
+        _ => {}
+    }
+    for dependency in dependencies {
+        visit(dependency, types, visited, output);
+    }
+    output.push(descriptor);
+}
+
+/// Names of the types directly referred to by a value
+fn references<'a, Metadata: Default>(descriptor: &'a Descriptor<Metadata>, output: &mut BTreeSet<&'a str>) {
+    match &descriptor.kind {
+        Kind::Struct { name, .. } | Kind::Aliased { name, .. } | Kind::Enum { name, .. } => { output.insert(name); },
+        Kind::Sequence(kind) | Kind::Option(kind) => references(kind, output),
+        Kind::Mapping(key, value) => {
+            references(key, output);
+            references(value, output);
+        },
+        _ => {}
+    }
+}
+
+/// Render the annotation for a value
+fn type_expression<Metadata: Default>(descriptor: &Descriptor<Metadata>, imports: &mut Imports) -> String {
+    match &descriptor.kind {
+        Kind::Struct { name, .. } |
+        Kind::Aliased { name, .. } |
+        Kind::Enum { name, .. } => name.to_string(),
+        Kind::Sequence(kind) => format!("list[{}]", type_expression(kind, imports)),
+        Kind::Option(kind) => {
+            imports.typing.insert("Optional");
+            format!("Optional[{}]", type_expression(kind, imports))
+        },
+        Kind::Mapping(key, value) => format!("dict[{}, {}]", type_expression(key, imports), type_expression(value, imports)),
+        Kind::DateTime => {
+            imports.datetime = true;
+            "datetime.datetime".to_owned()
+        },
+        Kind::String => "str".to_owned(),
+        Kind::U128 | Kind::I128 | Kind::U64 | Kind::I64 | Kind::U32 | Kind::I32 | Kind::U16 | Kind::I16 | Kind::U8 | Kind::I8 => "int".to_owned(),
+        Kind::F64 | Kind::F32 => "float".to_owned(),
+        Kind::Bool => "bool".to_owned(),
+        Kind::JSON | Kind::Any => {
+            imports.typing.insert("Any");
+            "Any".to_owned()
+        },
+    }
+}
+
+/// Default argument for a field, fields with a serde default are assumed to use `Default::default()`
+fn default_value<Metadata: Default>(entry: &Entry<Metadata>) -> Option<String> {
+    if let Kind::Option(_) = entry.type_info.kind {
+        return Some("default=None".to_owned());
+    }
+    if !entry.has_default && !entry.skip_deserializing {
+        return None
+    }
+    Some(match &unalias(&entry.type_info).kind {
+        Kind::String => "default=\"\"".to_owned(),
+        Kind::U128 | Kind::I128 | Kind::U64 | Kind::I64 | Kind::U32 | Kind::I32 | Kind::U16 | Kind::I16 | Kind::U8 | Kind::I8 => "default=0".to_owned(),
+        Kind::F64 | Kind::F32 => "default=0.0".to_owned(),
+        Kind::Bool => "default=False".to_owned(),
+        Kind::Sequence(_) => "default_factory=list".to_owned(),
+        Kind::Mapping(..) => "default_factory=dict".to_owned(),
+        Kind::Struct { name, .. } => format!("default_factory={name}"),
+        _ => return None,
+    })
+}
+
+/// Write a docstring at the given indentation, nothing is written for empty docs
+fn write_docstring(output: &mut String, indent: &str, lines: &[&str]) {
+    match lines {
+        [] => {},
+        [line] => {
+            let _ = writeln!(output, "{indent}\"\"\"{}\"\"\"", escape_docstring(line));
+        },
+        lines => {
+            let _ = writeln!(output, "{indent}\"\"\"{}", escape_docstring(lines[0]));
+            for line in &lines[1..] {
+                if line.is_empty() {
+                    output.push('\n');
+                } else {
+                    let _ = writeln!(output, "{indent}{}", escape_docstring(line));
+                }
+            }
+            let _ = writeln!(output, "{indent}\"\"\"");
+        }
+    }
+}
+
+/// Make a line safe to include in a triple quoted string
+fn escape_docstring(line: &str) -> String {
+    line.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"")
+}
+
+/// Python keywords that can't be used as attribute names
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Build a valid python identifier from a field label
+fn attribute_name(label: &str) -> String {
+    let mut name = field_name(label, '_');
+    if KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+/// Build an enum member name from a variant label
+fn member_name(label: &str) -> String {
+    attribute_name(&constant_name(label))
+}
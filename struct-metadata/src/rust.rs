@@ -0,0 +1,266 @@
+//! Generate Rust source from descriptors.
+//!
+//! Structs, enums and aliased types are written with `#[derive(Described)]` along with the
+//! doc comments, `#[serde(...)]` and `#[metadata(...)]` attributes needed for the derive to
+//! produce the same descriptor again. Labels that aren't usable as Rust names are kept with
+//! `rename`. No Rust type derives `Kind::Any`, it is written as `serde_json::Value` like
+//! `Kind::JSON` and comes back as `Kind::JSON`.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::named::named_types;
+use crate::{Descriptor, Kind};
+
+/// Metadata settings written back as `#[metadata(...)]` attributes
+pub trait RustMetadata {
+    /// Key and value pairs of the attribute, values are written as Rust expressions
+    fn attributes(&self) -> Vec<(String, String)> { vec![] }
+}
+
+/// Writes every entry sorted by key, values are the source text captured by the derive macro
+impl<K: AsRef<str>, V: AsRef<str>> RustMetadata for HashMap<K, V> {
+    fn attributes(&self) -> Vec<(String, String)> {
+        let mut attributes: Vec<_> = self.iter().map(|(key, value)| (key.as_ref().to_owned(), value.as_ref().to_owned())).collect();
+        attributes.sort();
+        attributes
+    }
+}
+
+/// Words that can't be used as plain identifiers
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while", "abstract", "become", "box", "do", "final", "macro", "override",
+    "priv", "typeof", "unsized", "virtual", "yield", "try", "gen",
+];
+
+/// Configurable Rust source generator
+#[derive(Debug, Clone)]
+pub struct Rust {
+    /// Traits derived on every type, `Described` is always added
+    derives: Vec<String>,
+    /// Visibility written before types and fields
+    visibility: String,
+}
+
+impl Default for Rust {
+    fn default() -> Self {
+        Self {
+            derives: vec!["Debug".to_owned(), "Clone".to_owned()],
+            visibility: "pub ".to_owned(),
+        }
+    }
+}
+
+impl Rust {
+    /// Create a generator with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a trait derived on every type, such as `serde::Deserialize`
+    pub fn derive(mut self, name: &str) -> Self {
+        self.derives.push(name.to_owned());
+        self
+    }
+
+    /// Replace the traits derived on every type, `Described` is always added
+    pub fn derives(mut self, names: &[&str]) -> Self {
+        self.derives = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Set the visibility of types and fields (`pub` by default, empty for private)
+    pub fn visibility(mut self, visibility: &str) -> Self {
+        self.visibility = if visibility.is_empty() { String::new() } else { format!("{visibility} ") };
+        self
+    }
+
+    /// Generate definitions for every named type used by the given descriptors
+    pub fn generate<'a, Metadata: Default + RustMetadata + 'a>(&self, roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> String {
+        let types = named_types(roots);
+        let mut body = String::new();
+        let mut uses = vec![];
+        let mut derives = self.derives.clone();
+        derives.push("Described".to_owned());
+        let derives = derives.join(", ");
+        let visibility = &self.visibility;
+
+        for descriptor in types.values() {
+            body.push('\n');
+            write_docs(&mut body, "", &descriptor.docs);
+            let _ = writeln!(body, "#[derive({derives})]");
+            write_metadata(&mut body, "", &descriptor.metadata);
+            match &descriptor.kind {
+                Kind::Struct { name, children } => {
+                    let ident = type_ident(name);
+                    if ident != *name {
+                        let _ = writeln!(body, "#[serde(rename = {name:?})]");
+                    }
+                    if children.is_empty() {
+                        let _ = writeln!(body, "{visibility}struct {ident} {{}}");
+                        continue
+                    }
+                    let _ = writeln!(body, "{visibility}struct {ident} {{");
+                    for child in children {
+                        let field = field_ident(&child.label);
+                        write_docs(&mut body, "    ", &child.docs);
+                        write_metadata(&mut body, "    ", &child.metadata);
+                        let mut serde = vec![];
+                        if field != child.label {
+                            serde.push(format!("rename = {:?}", child.label));
+                        }
+                        serde.extend(extra_aliases(&child.label, &child.aliases).map(|alias| format!("alias = {alias:?}")));
+                        if child.has_default {
+                            serde.push("default".to_owned());
+                        }
+                        match (child.skip_serializing, child.skip_deserializing) {
+                            (true, true) => serde.push("skip".to_owned()),
+                            (true, false) => serde.push("skip_serializing".to_owned()),
+                            (false, true) => serde.push("skip_deserializing".to_owned()),
+                            (false, false) => {},
+                        }
+                        if !serde.is_empty() {
+                            let _ = writeln!(body, "    #[serde({})]", serde.join(", "));
+                        }
+                        let _ = writeln!(body, "    {visibility}{field}: {},", type_expression(&child.type_info, &mut uses));
+                    }
+                    body.push_str("}\n");
+                },
+                Kind::Aliased { name, kind } => {
+                    let ident = type_ident(name);
+                    if ident != *name {
+                        let _ = writeln!(body, "#[serde(rename = {name:?})]");
+                    }
+                    let _ = writeln!(body, "{visibility}struct {ident}({visibility}{});", type_expression(kind, &mut uses));
+                },
+                Kind::Enum { name, variants } => {
+                    let ident = type_ident(name);
+                    if ident != *name {
+                        let _ = writeln!(body, "#[serde(rename = {name:?})]");
+                    }
+                    let _ = writeln!(body, "{visibility}enum {ident} {{");
+                    for variant in variants {
+                        let variant_ident = type_ident(&variant.label);
+                        write_docs(&mut body, "    ", &variant.docs);
+                        write_metadata(&mut body, "    ", &variant.metadata);
+                        let mut serde = vec![];
+                        if variant_ident != variant.label {
+                            serde.push(format!("rename = {:?}", variant.label));
+                        }
+                        serde.extend(extra_aliases(&variant.label, &variant.aliases).map(|alias| format!("alias = {alias:?}")));
+                        if !serde.is_empty() {
+                            let _ = writeln!(body, "    #[serde({})]", serde.join(", "));
+                        }
+                        let _ = writeln!(body, "    {variant_ident},");
+                    }
+                    body.push_str("}\n");
+                },
+                _ => {}
+            }
+        }
+
+        uses.push("struct_metadata::Described");
+        uses.sort();
+        uses.dedup();
+        let mut output = String::new();
+        for path in uses {
+            let _ = writeln!(output, "use {path};");
+        }
+        output + &body
+    }
+}
+
+/// Aliases other than the label, the derive macro adds the label itself
+fn extra_aliases<'a>(label: &'a str, aliases: &'a [Cow<'static, str>]) -> impl Iterator<Item = &'a str> {
+    let mut skipped = false;
+    aliases.iter().map(AsRef::as_ref).filter(move |alias| {
+        if !skipped && *alias == label {
+            skipped = true;
+            return false
+        }
+        true
+    })
+}
+
+/// Write doc strings as doc comments
+fn write_docs(output: &mut String, indent: &str, docs: &Option<Vec<Cow<'static, str>>>) {
+    for line in docs.iter().flatten() {
+        if line.is_empty() {
+            let _ = writeln!(output, "{indent}///");
+        } else {
+            let _ = writeln!(output, "{indent}/// {line}");
+        }
+    }
+}
+
+/// Write metadata as a `#[metadata(...)]` attribute
+fn write_metadata<Metadata: RustMetadata>(output: &mut String, indent: &str, metadata: &Metadata) {
+    let attributes = metadata.attributes();
+    if !attributes.is_empty() {
+        let attributes: Vec<_> = attributes.iter().map(|(key, value)| format!("{key}: {value}")).collect();
+        let _ = writeln!(output, "{indent}#[metadata({})]", attributes.join(", "));
+    }
+}
+
+/// Render the Rust type of a value, recording the imports it needs
+fn type_expression<Metadata: Default>(descriptor: &Descriptor<Metadata>, uses: &mut Vec<&'static str>) -> String {
+    match &descriptor.kind {
+        Kind::Struct { name, .. } |
+        Kind::Aliased { name, .. } |
+        Kind::Enum { name, .. } => type_ident(name),
+        Kind::Sequence(kind) => format!("Vec<{}>", type_expression(kind, uses)),
+        Kind::Option(kind) => format!("Option<{}>", type_expression(kind, uses)),
+        Kind::Mapping(key, value) => {
+            uses.push("std::collections::HashMap");
+            format!("HashMap<{}, {}>", type_expression(key, uses), type_expression(value, uses))
+        },
+        Kind::DateTime => "chrono::DateTime<chrono::Utc>".to_owned(),
+        // nothing derives Any, the closest type is read back as JSON
+        Kind::JSON | Kind::Any => "serde_json::Value".to_owned(),
+        kind => kind.type_expression(),
+    }
+}
+
+/// Build a field name from a label, converting it to snake case
+fn field_ident(label: &str) -> String {
+    let mut name = String::new();
+    let mut previous_lower = false;
+    for c in label.chars() {
+        if c.is_ascii_uppercase() && previous_lower {
+            name.push('_');
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        name.push(if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' });
+    }
+    valid_ident(name)
+}
+
+/// Build a type or variant name from a label, converting it to upper camel case
+fn type_ident(label: &str) -> String {
+    let mut name = String::new();
+    let mut upper = true;
+    for c in label.chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(if upper { c.to_ascii_uppercase() } else { c });
+            upper = false;
+        } else {
+            upper = true;
+        }
+    }
+    valid_ident(name)
+}
+
+/// Make sure a name isn't empty, a keyword or starts with a digit
+fn valid_ident(mut name: String) -> String {
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
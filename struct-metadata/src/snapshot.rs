@@ -0,0 +1,329 @@
+//! Snapshot files of descriptors for detecting schema drift in tests.
+//!
+//! A snapshot is a canonical JSON rendering of a descriptor, with object keys sorted and
+//! empty settings left out, written to `<directory>/<Type>.json`. Checking a descriptor
+//! compares it against the committed file and reports every changed path. When the
+//! `UPDATE_SCHEMAS` environment variable is set to `1` the files are written instead.
+
+use core::fmt;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::{Descriptor, Kind};
+
+/// Environment variable that switches checks to writing snapshots
+pub const UPDATE_VARIABLE: &str = "UPDATE_SCHEMAS";
+
+/// Metadata support needed to include metadata in snapshots
+pub trait SnapshotMetadata {
+    /// JSON rendering of this metadata, `null` or an empty object leaves it out of the snapshot
+    fn snapshot(&self) -> Value { Value::Null }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> SnapshotMetadata for HashMap<K, V> {
+    fn snapshot(&self) -> Value {
+        Value::Object(self.iter().map(|(key, value)| (key.as_ref().to_owned(), json!(value.as_ref()))).collect())
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> SnapshotMetadata for Vec<(K, V)> {
+    fn snapshot(&self) -> Value {
+        Value::Array(self.iter().map(|(key, value)| json!([key.as_ref(), value.as_ref()])).collect())
+    }
+}
+
+/// Errors checking a descriptor against its snapshot
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SnapshotError {
+    /// No snapshot has been written for the type
+    Missing {
+        /// Expected location of the snapshot
+        path: PathBuf,
+    },
+    /// The descriptor no longer matches the snapshot
+    Changed {
+        /// Location of the snapshot
+        path: PathBuf,
+        /// Description of each changed path
+        differences: Vec<String>,
+    },
+    /// The snapshot couldn't be read or written
+    Io {
+        /// Location of the snapshot
+        path: PathBuf,
+        /// Error reported by the file system or parser
+        message: String,
+    },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Missing { path } => write!(f, "schema snapshot {} doesn't exist, run with {UPDATE_VARIABLE}=1 to create it", path.display()),
+            SnapshotError::Changed { path, differences } => {
+                write!(f, "schema snapshot {} is out of date, run with {UPDATE_VARIABLE}=1 to update it", path.display())?;
+                for difference in differences {
+                    write!(f, "\n    {difference}")?;
+                }
+                Ok(())
+            },
+            SnapshotError::Io { path, message } => write!(f, "schema snapshot {}: {message}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// Checks or writes snapshot files in a directory
+#[derive(Debug, Clone)]
+pub struct Snapshots {
+    /// Directory holding the snapshot files
+    directory: PathBuf,
+    /// Write snapshots rather than comparing against them
+    update: bool,
+}
+
+impl Snapshots {
+    /// Use snapshots in the given directory, they are written rather than checked when the
+    /// `UPDATE_SCHEMAS` environment variable is set to `1`
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        Self {
+            directory: directory.as_ref().to_owned(),
+            update: std::env::var(UPDATE_VARIABLE).is_ok_and(|value| value == "1"),
+        }
+    }
+
+    /// Choose between writing and checking snapshots regardless of the environment
+    pub fn update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Location of the snapshot for a name
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.directory.join(format!("{name}.json"))
+    }
+
+    /// Check a descriptor against the snapshot named after its type
+    pub fn check<Metadata: Default + SnapshotMetadata>(&self, descriptor: &Descriptor<Metadata>) -> Result<(), SnapshotError> {
+        self.check_named(&type_name(&descriptor.kind), descriptor)
+    }
+
+    /// Check a descriptor against the snapshot with the given name
+    pub fn check_named<Metadata: Default + SnapshotMetadata>(&self, name: &str, descriptor: &Descriptor<Metadata>) -> Result<(), SnapshotError> {
+        let path = self.path(name);
+        let actual = snapshot(descriptor);
+        let io = |error: &dyn fmt::Display| SnapshotError::Io { path: path.clone(), message: error.to_string() };
+
+        if self.update {
+            let text = canonical(&actual);
+            if std::fs::read_to_string(&path).ok().as_deref() != Some(text.as_str()) {
+                std::fs::create_dir_all(&self.directory).map_err(|error| io(&error))?;
+                std::fs::write(&path, text).map_err(|error| io(&error))?;
+            }
+            return Ok(())
+        }
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Err(SnapshotError::Missing { path }),
+            Err(error) => return Err(io(&error)),
+        };
+        let expected: Value = serde_json::from_str(&text).map_err(|error| io(&error))?;
+        let differences = differences(&expected, &actual);
+        if differences.is_empty() {
+            Ok(())
+        } else {
+            Err(SnapshotError::Changed { path, differences })
+        }
+    }
+
+    /// Check a descriptor against the snapshot named after its type, panicking with the
+    /// differences found
+    pub fn assert<Metadata: Default + SnapshotMetadata>(&self, descriptor: &Descriptor<Metadata>) {
+        if let Err(error) = self.check(descriptor) {
+            panic!("{error}");
+        }
+    }
+}
+
+/// Check a descriptor against the snapshot in the `schemas` directory of the crate being
+/// tested, snapshots are written instead when `UPDATE_SCHEMAS=1` is set
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($descriptor:expr) => {
+        $crate::snapshot::Snapshots::new(concat!(env!("CARGO_MANIFEST_DIR"), "/schemas")).assert(&$descriptor)
+    };
+    ($directory:expr, $descriptor:expr) => {
+        $crate::snapshot::Snapshots::new($directory).assert(&$descriptor)
+    };
+}
+
+/// Name used for the snapshot of a type
+fn type_name<Metadata: Default>(kind: &Kind<Metadata>) -> String {
+    match kind {
+        Kind::Struct { name, .. } | Kind::Aliased { name, .. } | Kind::Enum { name, .. } => name.to_string(),
+        kind => kind.type_expression(),
+    }
+}
+
+/// Render a snapshot as the text written to a file
+pub fn canonical(snapshot: &Value) -> String {
+    let mut text = serde_json::to_string_pretty(snapshot).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+/// Build the canonical JSON rendering of a descriptor
+pub fn snapshot<Metadata: Default + SnapshotMetadata>(descriptor: &Descriptor<Metadata>) -> Value {
+    let mut output = Map::new();
+    insert_common(&mut output, &descriptor.docs, &descriptor.metadata, &[], "");
+    match &descriptor.kind {
+        Kind::Struct { name, children } => {
+            output.insert("kind".to_owned(), json!("struct"));
+            output.insert("name".to_owned(), json!(name));
+            let fields: Vec<Value> = children.iter().map(|child| {
+                let mut field = Map::new();
+                field.insert("label".to_owned(), json!(child.label));
+                insert_common(&mut field, &child.docs, &child.metadata, &child.aliases, &child.label);
+                field.insert("type".to_owned(), snapshot(&child.type_info));
+                for (key, set) in [("default", child.has_default), ("skip_serializing", child.skip_serializing), ("skip_deserializing", child.skip_deserializing)] {
+                    if set {
+                        field.insert(key.to_owned(), json!(true));
+                    }
+                }
+                Value::Object(field)
+            }).collect();
+            output.insert("fields".to_owned(), Value::Array(fields));
+        },
+        Kind::Enum { name, variants } => {
+            output.insert("kind".to_owned(), json!("enum"));
+            output.insert("name".to_owned(), json!(name));
+            let variants: Vec<Value> = variants.iter().map(|variant| {
+                let mut entry = Map::new();
+                entry.insert("label".to_owned(), json!(variant.label));
+                insert_common(&mut entry, &variant.docs, &variant.metadata, &variant.aliases, &variant.label);
+                Value::Object(entry)
+            }).collect();
+            output.insert("variants".to_owned(), Value::Array(variants));
+        },
+        Kind::Aliased { name, kind } => {
+            output.insert("kind".to_owned(), json!("aliased"));
+            output.insert("name".to_owned(), json!(name));
+            output.insert("type".to_owned(), snapshot(kind));
+        },
+        Kind::Sequence(kind) => {
+            output.insert("kind".to_owned(), json!("sequence"));
+            output.insert("items".to_owned(), snapshot(kind));
+        },
+        Kind::Option(kind) => {
+            output.insert("kind".to_owned(), json!("option"));
+            output.insert("type".to_owned(), snapshot(kind));
+        },
+        Kind::Mapping(key, value) => {
+            output.insert("kind".to_owned(), json!("mapping"));
+            output.insert("key".to_owned(), snapshot(key));
+            output.insert("value".to_owned(), snapshot(value));
+        },
+        Kind::DateTime => {
+            output.insert("kind".to_owned(), json!("datetime"));
+        },
+        Kind::JSON => {
+            output.insert("kind".to_owned(), json!("json"));
+        },
+        Kind::Any => {
+            output.insert("kind".to_owned(), json!("any"));
+        },
+        kind => {
+            output.insert("kind".to_owned(), json!(kind.type_expression()));
+        },
+    }
+    Value::Object(output)
+}
+
+/// Add the docs, metadata and aliases shared by types, fields and variants
+fn insert_common<Metadata: SnapshotMetadata>(output: &mut Map<String, Value>, docs: &Option<Vec<Cow<'static, str>>>, metadata: &Metadata, aliases: &[Cow<'static, str>], label: &str) {
+    if let Some(docs) = docs {
+        output.insert("docs".to_owned(), json!(docs));
+    }
+    let metadata = metadata.snapshot();
+    let empty = match &metadata {
+        Value::Null => true,
+        Value::Object(items) => items.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    };
+    if !empty {
+        output.insert("metadata".to_owned(), metadata);
+    }
+    let aliases: Vec<_> = aliases.iter().filter(|alias| **alias != label).collect();
+    if !aliases.is_empty() {
+        output.insert("aliases".to_owned(), json!(aliases));
+    }
+}
+
+/// Describe every path where two snapshots differ.
+///
+/// Fields and variants are identified by their label rather than their position so that
+/// adding a field reports just that field.
+pub fn differences(expected: &Value, actual: &Value) -> Vec<String> {
+    let mut output = vec![];
+    compare("", expected, actual, &mut output);
+    output
+}
+
+/// Recursive helper for `differences`
+fn compare(path: &str, expected: &Value, actual: &Value, output: &mut Vec<String>) {
+    let location = if path.is_empty() { "(root)" } else { path };
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, value) in expected {
+                match actual.get(key) {
+                    Some(other) => compare(&join(path, key), value, other, output),
+                    None => output.push(format!("{}: removed (was {value})", join(path, key))),
+                }
+            }
+            for (key, value) in actual {
+                if !expected.contains_key(key) {
+                    output.push(format!("{}: added {value}", join(path, key)));
+                }
+            }
+        },
+        (Value::Array(expected), Value::Array(actual)) if labelled(expected) && labelled(actual) => {
+            let label = |value: &Value| value.get("label").and_then(Value::as_str).unwrap_or_default().to_owned();
+            let expected_labels: Vec<String> = expected.iter().map(label).collect();
+            let actual_labels: Vec<String> = actual.iter().map(label).collect();
+            for (name, value) in expected_labels.iter().zip(expected) {
+                match actual_labels.iter().position(|other| other == name) {
+                    Some(index) => compare(&join(path, name), value, &actual[index], output),
+                    None => output.push(format!("{}: removed", join(path, name))),
+                }
+            }
+            for name in &actual_labels {
+                if !expected_labels.contains(name) {
+                    output.push(format!("{}: added", join(path, name)));
+                }
+            }
+            let common: Vec<_> = expected_labels.iter().filter(|name| actual_labels.contains(name)).collect();
+            let reordered: Vec<_> = actual_labels.iter().filter(|name| expected_labels.contains(name)).collect();
+            if common != reordered {
+                output.push(format!("{location}: order changed from [{}] to [{}]", expected_labels.join(", "), actual_labels.join(", ")));
+            }
+        },
+        (expected, actual) if expected != actual => output.push(format!("{location}: {expected} -> {actual}")),
+        _ => {},
+    }
+}
+
+/// Are all the items of a list objects with a label
+fn labelled(items: &[Value]) -> bool {
+    items.iter().all(|item| item.get("label").is_some_and(Value::is_string))
+}
+
+/// Extend a path with a key
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() { key.to_owned() } else { format!("{path}.{key}") }
+}
@@ -0,0 +1,904 @@
+//! Generate SQL `CREATE TABLE` statements from descriptors.
+//!
+//! A struct descriptor is first converted into a set of [`Table`] definitions for a
+//! [`Dialect`], which are then rendered into statements. Nested structs are flattened into
+//! columns prefixed with the field name unless their metadata asks for JSON storage, lists and
+//! maps are stored as JSON unless their metadata asks for a child table keyed by the primary
+//! key of the parent. How fields are stored is read through the [`SqlMetadata`] trait.
+
+use core::fmt;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::named::{unalias, unquote};
+use crate::{Descriptor, Entry, Kind};
+
+/// SQL dialects statements can be generated for
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Dialect {
+    /// SQLite 3
+    Sqlite,
+    /// PostgreSQL
+    Postgres,
+    /// MySQL 8
+    MySql,
+}
+
+/// How a nested struct, list or map is stored
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Storage {
+    /// Struct fields become columns prefixed with the field name (the default for structs)
+    Flatten,
+    /// The value is stored in a single JSON column (the default for lists and maps)
+    Json,
+    /// List items or map entries are stored in a child table
+    Table,
+}
+
+/// Metadata settings that control the generated tables
+pub trait SqlMetadata {
+    /// Is this field part of the primary key
+    fn primary_key(&self) -> bool { false }
+    /// Should an index be created for this field
+    fn indexed(&self) -> bool { false }
+    /// Should a unique index be created for this field
+    fn unique(&self) -> bool { false }
+    /// How a nested struct, list or map is stored
+    fn storage(&self) -> Option<Storage> { None }
+    /// Override the column type chosen for this field
+    fn column_type(&self) -> Option<String> { None }
+    /// Override the table name used for a struct, read from the struct metadata
+    fn table_name(&self) -> Option<String> { None }
+}
+
+/// Reads the `primary_key`, `index`, `unique`, `storage` (`flatten`, `json` or `table`),
+/// `sql_type` and `table` keys
+impl<K: AsRef<str>, V: AsRef<str>> SqlMetadata for HashMap<K, V> {
+    fn primary_key(&self) -> bool {
+        self.iter().any(|(key, value)| key.as_ref() == "primary_key" && unquote(value.as_ref()) == "true")
+    }
+
+    fn indexed(&self) -> bool {
+        self.iter().any(|(key, value)| key.as_ref() == "index" && unquote(value.as_ref()) == "true")
+    }
+
+    fn unique(&self) -> bool {
+        self.iter().any(|(key, value)| key.as_ref() == "unique" && unquote(value.as_ref()) == "true")
+    }
+
+    fn storage(&self) -> Option<Storage> {
+        self.iter().find(|(key, _)| key.as_ref() == "storage").and_then(|(_, value)| match unquote(value.as_ref()) {
+            "flatten" => Some(Storage::Flatten),
+            "json" => Some(Storage::Json),
+            "table" => Some(Storage::Table),
+            _ => None,
+        })
+    }
+
+    fn column_type(&self) -> Option<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "sql_type").map(|(_, value)| unquote(value.as_ref()).to_owned())
+    }
+
+    fn table_name(&self) -> Option<String> {
+        self.iter().find(|(key, _)| key.as_ref() == "table").map(|(_, value)| unquote(value.as_ref()).to_owned())
+    }
+}
+
+/// Reasons a descriptor can't be converted into tables
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SqlError {
+    /// Only structs can be stored as tables
+    NotAStruct {
+        /// Type that was given
+        name: String,
+    },
+    /// A child table was requested for a table without a primary key to refer to
+    MissingPrimaryKey {
+        /// Table without a primary key
+        table: String,
+        /// Column that asked for a child table
+        column: String,
+    },
+    /// A storage setting that doesn't apply to the type of the field
+    Unsupported {
+        /// Column the setting was given for
+        column: String,
+        /// Why the setting can't be used
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for SqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlError::NotAStruct { name } => write!(f, "{name} is not a struct"),
+            SqlError::MissingPrimaryKey { table, column } => write!(f, "{table}.{column}: child tables need a primary key on {table}"),
+            SqlError::Unsupported { column, reason } => write!(f, "{column}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SqlError {}
+
+/// A table definition
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Table {
+    /// Name of the table
+    pub name: String,
+    /// Description of the table
+    pub comment: Option<String>,
+    /// Columns in declaration order
+    pub columns: Vec<Column>,
+    /// Names of the primary key columns
+    pub primary_key: Vec<String>,
+    /// Reference to the parent of a child table
+    pub foreign_key: Option<ForeignKey>,
+    /// Indexes on this table
+    pub indexes: Vec<Index>,
+}
+
+impl Table {
+    /// Find a column by name
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
+
+/// A column definition
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Column {
+    /// Name of the column
+    pub name: String,
+    /// Other names the column may have had, from the aliases of the field
+    pub aliases: Vec<String>,
+    /// Type of the column in the dialect the table was built for
+    pub sql_type: String,
+    /// Can the column hold NULL
+    pub nullable: bool,
+    /// Values the column is limited to by a CHECK constraint
+    pub allowed: Option<Vec<String>>,
+    /// Description of the column
+    pub comment: Option<String>,
+}
+
+/// Reference from a child table to its parent
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ForeignKey {
+    /// Columns of the child table
+    pub columns: Vec<String>,
+    /// Name of the parent table
+    pub table: String,
+    /// Primary key columns of the parent table
+    pub references: Vec<String>,
+}
+
+/// An index over some columns of a table
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Index {
+    /// Name of the index
+    pub name: String,
+    /// Indexed columns
+    pub columns: Vec<String>,
+    /// Is this a unique index
+    pub unique: bool,
+}
+
+/// A field of a table waiting for its child table to be built
+struct Pending<'a, Metadata: Default> {
+    /// Column name the child table is named after
+    name: String,
+    /// Type of the field
+    descriptor: &'a Descriptor<Metadata>,
+    /// Description of the field
+    comment: Option<String>,
+}
+
+/// SQL DDL generator for a dialect
+#[derive(Debug, Clone)]
+pub struct Sql {
+    /// Dialect used for types and statements
+    dialect: Dialect,
+}
+
+impl Sql {
+    /// Create a generator for a dialect
+    pub fn new(dialect: Dialect) -> Self {
+        Self { dialect }
+    }
+
+    /// Dialect this generator writes
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Convert a struct into the table for it followed by any child tables
+    pub fn tables<Metadata: Default + SqlMetadata>(&self, descriptor: &Descriptor<Metadata>) -> Result<Vec<Table>, SqlError> {
+        let Kind::Struct { name, children } = &unalias(descriptor).kind else {
+            return Err(SqlError::NotAStruct { name: descriptor.kind.type_expression() })
+        };
+        let mut table = Table {
+            name: descriptor.metadata.table_name().unwrap_or_else(|| snake_case(name)),
+            comment: comment(&descriptor.docs),
+            columns: vec![],
+            primary_key: vec![],
+            foreign_key: None,
+            indexes: vec![],
+        };
+        let mut pending = vec![];
+        self.struct_columns(&mut table, &mut pending, &[], children, false)?;
+        self.finish(table, pending)
+    }
+
+    /// Build the statements creating the tables for a struct
+    pub fn create_tables<Metadata: Default + SqlMetadata>(&self, descriptor: &Descriptor<Metadata>) -> Result<String, SqlError> {
+        let statements: Vec<String> = self.tables(descriptor)?.iter().flat_map(|table| self.create_table(table)).collect();
+        Ok(statements.join("\n\n") + "\n")
+    }
+
+    /// Build the statements creating a table, its comments and indexes
+    pub fn create_table(&self, table: &Table) -> Vec<String> {
+        let mut output = vec![self.create_statement(table, &table.name)];
+        if self.dialect == Dialect::Postgres {
+            if let Some(comment) = &table.comment {
+                output.push(format!("COMMENT ON TABLE {} IS {};", self.quote(&table.name), string_literal(comment)));
+            }
+            for column in &table.columns {
+                if let Some(comment) = &column.comment {
+                    output.push(format!("COMMENT ON COLUMN {}.{} IS {};", self.quote(&table.name), self.quote(&column.name), string_literal(comment)));
+                }
+            }
+        }
+        for index in &table.indexes {
+            output.push(self.create_index(&table.name, index));
+        }
+        output
+    }
+
+    /// Build the `CREATE TABLE` statement for a table under the given name, constraints are
+    /// named after the table definition so they keep their names when a copy is renamed
+    fn create_statement(&self, table: &Table, name: &str) -> String {
+        let mut statement = String::new();
+        if self.dialect == Dialect::Sqlite {
+            for line in table.comment.iter().flat_map(|comment| comment.lines()) {
+                statement += &format!("-- {line}\n");
+            }
+        }
+        statement += &format!("CREATE TABLE {} (\n", self.quote(name));
+
+        let mut lines = vec![];
+        for column in &table.columns {
+            let mut line = String::new();
+            if self.dialect == Dialect::Sqlite {
+                for comment in column.comment.iter().flat_map(|comment| comment.lines()) {
+                    line += &format!("    -- {comment}\n");
+                }
+            }
+            line += &format!("    {}", self.column_definition(&table.name, column));
+            lines.push(line);
+        }
+        if !table.primary_key.is_empty() {
+            lines.push(format!("    PRIMARY KEY ({})", self.column_list(&table.primary_key)));
+        }
+        if let Some(foreign_key) = &table.foreign_key {
+            lines.push(format!("    FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE CASCADE",
+                self.column_list(&foreign_key.columns), self.quote(&foreign_key.table), self.column_list(&foreign_key.references)));
+        }
+        statement += &lines.join(",\n");
+        statement += "\n)";
+        if let (Dialect::MySql, Some(comment)) = (self.dialect, &table.comment) {
+            statement += &format!(" COMMENT={}", string_literal(comment));
+        }
+        statement += ";";
+        statement
+    }
+
+    /// Build the statement creating an index
+    pub fn create_index(&self, table: &str, index: &Index) -> String {
+        format!("CREATE {}INDEX {} ON {} ({});", if index.unique { "UNIQUE " } else { "" }, self.quote(&index.name), self.quote(table), self.column_list(&index.columns))
+    }
+
+    /// Render a column of a table as used within `CREATE TABLE` or `ADD COLUMN`
+    pub fn column_definition(&self, table: &str, column: &Column) -> String {
+        let mut output = self.column_spec(column);
+        if let Some(check) = self.check_constraint(table, column) {
+            output += " ";
+            output += &check;
+        }
+        if let (Dialect::MySql, Some(comment)) = (self.dialect, &column.comment) {
+            output += &format!(" COMMENT {}", string_literal(comment));
+        }
+        output
+    }
+
+    /// Render the name, type and nullability of a column
+    fn column_spec(&self, column: &Column) -> String {
+        let mut output = format!("{} {}", self.quote(&column.name), column.sql_type);
+        if !column.nullable {
+            output += " NOT NULL";
+        }
+        output
+    }
+
+    /// Render the named CHECK constraint limiting a column to its allowed values
+    fn check_constraint(&self, table: &str, column: &Column) -> Option<String> {
+        let allowed = column.allowed.as_ref()?;
+        let values: Vec<_> = allowed.iter().map(|value| string_literal(value)).collect();
+        Some(format!("CONSTRAINT {} CHECK ({} IN ({}))", self.quote(&check_name(table, &column.name)), self.quote(&column.name), values.join(", ")))
+    }
+
+    /// Quote an identifier
+    pub fn quote(&self, name: &str) -> String {
+        match self.dialect {
+            Dialect::MySql => format!("`{}`", name.replace('`', "``")),
+            Dialect::Sqlite | Dialect::Postgres => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    /// Quote and join a list of column names
+    fn column_list(&self, columns: &[String]) -> String {
+        columns.iter().map(|column| self.quote(column)).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Add the columns for the fields of a struct.
+    ///
+    /// Column names are prefixed with the names of the field holding the struct, `prefixes`
+    /// holds that name followed by its aliases. `nullable` is set inside optional values.
+    fn struct_columns<'a, Metadata: Default + SqlMetadata>(&self, table: &mut Table, pending: &mut Vec<Pending<'a, Metadata>>, prefixes: &[String], children: &'a [Entry<Metadata>], nullable: bool) -> Result<(), SqlError> {
+        for child in children {
+            if child.skip_serializing && child.skip_deserializing {
+                continue
+            }
+            let mut labels = vec![&child.label];
+            labels.extend(child.aliases.iter().filter(|alias| **alias != child.label));
+            let mut names: Vec<String> = vec![];
+            let candidates: Vec<String> = if prefixes.is_empty() {
+                labels.iter().map(|label| label.to_string()).collect()
+            } else {
+                prefixes.iter().flat_map(|prefix| labels.iter().map(move |label| format!("{prefix}_{label}"))).collect()
+            };
+            for candidate in candidates {
+                if !names.contains(&candidate) {
+                    names.push(candidate);
+                }
+            }
+            let count = table.columns.len();
+            self.value_columns(table, pending, &names, &child.type_info, &child.metadata, comment(&child.docs), nullable)?;
+            let name = names.swap_remove(0);
+            // flattened structs and child tables don't give the field a column of its own
+            let single_column = table.columns.len() == count + 1 && table.columns[count].name == name;
+
+            if child.metadata.primary_key() {
+                if !single_column || is_struct(&child.type_info) {
+                    return Err(SqlError::Unsupported { column: name, reason: "primary keys can only be scalar columns" })
+                }
+                table.primary_key.push(name.clone());
+            }
+            if child.metadata.unique() || child.metadata.indexed() {
+                if !single_column {
+                    return Err(SqlError::Unsupported { column: name, reason: "indexes can only be created on single columns" })
+                }
+                let unique = child.metadata.unique();
+                table.indexes.push(Index {
+                    name: format!("{}_{name}_{}", table.name, if unique { "key" } else { "idx" }),
+                    columns: vec![name],
+                    unique,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the columns needed to store a single value, `names` holds the column name followed by
+    /// the names it may have had
+    #[allow(clippy::too_many_arguments)]
+    fn value_columns<'a, Metadata: Default + SqlMetadata>(&self, table: &mut Table, pending: &mut Vec<Pending<'a, Metadata>>, names: &[String], descriptor: &'a Descriptor<Metadata>, metadata: &Metadata, comment: Option<String>, nullable: bool) -> Result<(), SqlError> {
+        let descriptor = unalias(descriptor);
+        let storage = metadata.storage();
+        let name = &names[0];
+        let mut column = Column { name: name.clone(), aliases: names[1..].to_vec(), sql_type: String::new(), nullable, allowed: None, comment };
+        match &descriptor.kind {
+            Kind::Option(kind) => return self.value_columns(table, pending, names, kind, metadata, column.comment, true),
+            Kind::Struct { children, .. } => match storage.unwrap_or(Storage::Flatten) {
+                Storage::Flatten => return self.struct_columns(table, pending, names, children, nullable),
+                Storage::Json => column.sql_type = self.json_type().to_owned(),
+                Storage::Table => return Err(SqlError::Unsupported { column: name.to_owned(), reason: "only lists and maps can be stored in child tables" }),
+            },
+            Kind::Sequence(_) | Kind::Mapping(..) => match storage.unwrap_or(Storage::Json) {
+                Storage::Json => column.sql_type = self.json_type().to_owned(),
+                Storage::Table => {
+                    pending.push(Pending { name: name.to_owned(), descriptor, comment: column.comment });
+                    return Ok(())
+                },
+                Storage::Flatten => return Err(SqlError::Unsupported { column: name.to_owned(), reason: "only structs can be flattened" }),
+            },
+            Kind::Enum { variants, .. } => {
+                column.sql_type = self.scalar_type(&descriptor.kind).to_owned();
+                column.allowed = Some(variants.iter().map(|variant| variant.label.to_string()).collect());
+            },
+            kind => column.sql_type = self.scalar_type(kind).to_owned(),
+        }
+        if let Some(sql_type) = metadata.column_type() {
+            column.sql_type = sql_type;
+        }
+        table.columns.push(column);
+        Ok(())
+    }
+
+    /// Complete a table once all its columns are known, building its child tables
+    fn finish<Metadata: Default + SqlMetadata>(&self, mut table: Table, pending: Vec<Pending<'_, Metadata>>) -> Result<Vec<Table>, SqlError> {
+        // MySQL can't index TEXT columns without a prefix length
+        if self.dialect == Dialect::MySql {
+            let keyed: Vec<String> = table.primary_key.iter().chain(table.indexes.iter().flat_map(|index| &index.columns)).cloned().collect();
+            for column in &mut table.columns {
+                if column.sql_type == "TEXT" && keyed.contains(&column.name) {
+                    column.sql_type = "VARCHAR(255)".to_owned();
+                }
+            }
+        }
+
+        let mut children = vec![];
+        for field in pending {
+            if table.primary_key.is_empty() {
+                return Err(SqlError::MissingPrimaryKey { table: table.name.clone(), column: field.name })
+            }
+            let mut child = Table {
+                name: format!("{}_{}", table.name, field.name),
+                comment: field.comment,
+                columns: vec![],
+                primary_key: vec![],
+                foreign_key: None,
+                indexes: vec![],
+            };
+            let mut references = vec![];
+            for key in &table.primary_key {
+                let Some(parent) = table.column(key) else {
+                    return Err(SqlError::Unsupported { column: key.clone(), reason: "primary key is not a column of the table" })
+                };
+                let name = format!("{}_{key}", table.name);
+                child.columns.push(Column { name: name.clone(), aliases: vec![], sql_type: parent.sql_type.clone(), nullable: false, allowed: None, comment: None });
+                child.primary_key.push(name.clone());
+                references.push(name);
+            }
+            child.foreign_key = Some(ForeignKey { columns: references, table: table.name.clone(), references: table.primary_key.clone() });
+
+            let mut grandchildren = vec![];
+            let value = match &field.descriptor.kind {
+                Kind::Sequence(item) => {
+                    child.columns.push(Column { name: "position".to_owned(), aliases: vec![], sql_type: self.scalar_type::<Metadata>(&Kind::U32).to_owned(), nullable: false, allowed: None, comment: None });
+                    child.primary_key.push("position".to_owned());
+                    item
+                },
+                Kind::Mapping(key, value) => {
+                    self.value_columns(&mut child, &mut grandchildren, &["key".to_owned()], key, &Metadata::default(), None, false)?;
+                    child.primary_key.push("key".to_owned());
+                    value
+                },
+                _ => unreachable!("only lists and maps are given child tables"),
+            };
+            match &unalias(value).kind {
+                Kind::Struct { children, .. } => self.struct_columns(&mut child, &mut grandchildren, &[], children, false)?,
+                _ => self.value_columns(&mut child, &mut grandchildren, &["value".to_owned()], value, &Metadata::default(), None, false)?,
+            }
+            children.extend(self.finish(child, grandchildren)?);
+        }
+
+        let mut output = vec![table];
+        output.extend(children);
+        Ok(output)
+    }
+
+    /// Column type used for JSON values
+    fn json_type(&self) -> &'static str {
+        match self.dialect {
+            Dialect::Sqlite => "TEXT",
+            Dialect::Postgres => "JSONB",
+            Dialect::MySql => "JSON",
+        }
+    }
+
+    /// Column type used for a scalar value
+    fn scalar_type<Metadata: Default>(&self, kind: &Kind<Metadata>) -> &'static str {
+        match self.dialect {
+            Dialect::Sqlite => match kind {
+                Kind::U64 | Kind::I64 | Kind::U32 | Kind::I32 | Kind::U16 | Kind::I16 | Kind::U8 | Kind::I8 | Kind::Bool => "INTEGER",
+                Kind::F64 | Kind::F32 => "REAL",
+                // 128 bit integers don't fit in an INTEGER and are kept as text
+                _ => "TEXT",
+            },
+            Dialect::Postgres => match kind {
+                Kind::U128 | Kind::I128 => "NUMERIC(39)",
+                Kind::U64 => "NUMERIC(20)",
+                Kind::I64 | Kind::U32 => "BIGINT",
+                Kind::I32 | Kind::U16 => "INTEGER",
+                Kind::I16 | Kind::U8 | Kind::I8 => "SMALLINT",
+                Kind::F64 => "DOUBLE PRECISION",
+                Kind::F32 => "REAL",
+                Kind::Bool => "BOOLEAN",
+                Kind::DateTime => "TIMESTAMPTZ",
+                Kind::JSON | Kind::Any => "JSONB",
+                _ => "TEXT",
+            },
+            Dialect::MySql => match kind {
+                Kind::U128 | Kind::I128 => "DECIMAL(39, 0)",
+                Kind::U64 => "BIGINT UNSIGNED",
+                Kind::I64 => "BIGINT",
+                Kind::U32 => "INT UNSIGNED",
+                Kind::I32 => "INT",
+                Kind::U16 => "SMALLINT UNSIGNED",
+                Kind::I16 => "SMALLINT",
+                Kind::U8 => "TINYINT UNSIGNED",
+                Kind::I8 => "TINYINT",
+                Kind::F64 => "DOUBLE",
+                Kind::F32 => "FLOAT",
+                Kind::Bool => "BOOLEAN",
+                Kind::DateTime => "DATETIME(6)",
+                Kind::JSON | Kind::Any => "JSON",
+                _ => "TEXT",
+            },
+        }
+    }
+}
+
+/// Is a value a struct, looking through aliases and options
+fn is_struct<Metadata: Default>(descriptor: &Descriptor<Metadata>) -> bool {
+    match &unalias(descriptor).kind {
+        Kind::Struct { .. } => true,
+        Kind::Option(kind) => is_struct(kind),
+        _ => false,
+    }
+}
+
+/// Join doc strings into a comment
+fn comment(docs: &Option<Vec<Cow<'static, str>>>) -> Option<String> {
+    let lines = crate::named::doc_lines(docs);
+    if lines.is_empty() { None } else { Some(lines.join("\n")) }
+}
+
+/// Name given to the CHECK constraint of a column
+fn check_name(table: &str, column: &str) -> String {
+    format!("{table}_{column}_check")
+}
+
+/// Quote a SQL string literal, which uses single quotes and doubles them to escape
+fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Convert a type name into a table name
+fn snake_case(name: &str) -> String {
+    let mut output = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && previous_lower {
+            output.push('_');
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        output.push(c.to_ascii_lowercase());
+    }
+    output
+}
+
+/// A single change within a migration
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Step {
+    /// What the step changes
+    pub description: String,
+    /// Statements to run, in order
+    pub statements: Vec<String>,
+    /// Can the step discard data or fail against existing rows
+    pub destructive: bool,
+}
+
+/// Statements that change the tables of an old version of a type into those of a new version
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Migration {
+    /// Steps in the order they should be run
+    pub steps: Vec<Step>,
+}
+
+impl Migration {
+    /// Are the tables unchanged
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Does any step need review before being run against existing data
+    pub fn is_destructive(&self) -> bool {
+        self.steps.iter().any(|step| step.destructive)
+    }
+
+    /// Add a step
+    fn push(&mut self, description: String, statements: Vec<String>, destructive: bool) {
+        self.steps.push(Step { description, statements, destructive });
+    }
+}
+
+/// Writes the migration as a script, each step is preceded by a comment describing it
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            if step.destructive {
+                writeln!(f, "-- DESTRUCTIVE: {}", step.description)?;
+            } else {
+                writeln!(f, "-- {}", step.description)?;
+            }
+            for statement in &step.statements {
+                writeln!(f, "{statement}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pairing between the columns of two versions of a table
+struct ColumnChanges<'a> {
+    /// Columns present in both versions, old column first
+    kept: Vec<(&'a Column, &'a Column)>,
+    /// Columns only in the new version
+    added: Vec<&'a Column>,
+    /// Columns only in the old version
+    removed: Vec<&'a Column>,
+}
+
+impl<'a> ColumnChanges<'a> {
+    /// Match columns by name, then by the aliases of the new columns
+    fn new(old: &'a Table, new: &'a Table) -> Self {
+        let mut pairs: Vec<Option<&'a Column>> = new.columns.iter().map(|column| old.column(&column.name)).collect();
+        for (index, column) in new.columns.iter().enumerate() {
+            if pairs[index].is_some() {
+                continue
+            }
+            pairs[index] = old.columns.iter().find(|candidate| {
+                column.aliases.contains(&candidate.name)
+                    && new.column(&candidate.name).is_none()
+                    && !pairs.iter().flatten().any(|used| used.name == candidate.name)
+            });
+        }
+
+        let mut output = Self { kept: vec![], added: vec![], removed: vec![] };
+        for (column, pair) in new.columns.iter().zip(&pairs) {
+            match pair {
+                Some(old) => output.kept.push((old, column)),
+                None => output.added.push(column),
+            }
+        }
+        output.removed = old.columns.iter().filter(|column| !pairs.iter().flatten().any(|used| used.name == column.name)).collect();
+        output
+    }
+}
+
+/// Can existing values fail the CHECK constraint of the new version of a column
+fn narrows_allowed(old: &Column, new: &Column) -> bool {
+    match (&old.allowed, &new.allowed) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(old), Some(new)) => old.iter().any(|value| !new.contains(value)),
+    }
+}
+
+impl Sql {
+    /// Build the migration from the tables of one version of a struct to those of another.
+    ///
+    /// Renamed fields are detected through the aliases of the new version. On SQLite any
+    /// change that `ALTER TABLE` can't express rebuilds the table by copying it into a new one.
+    /// Changes to the primary key of a table are not followed into the foreign keys of its
+    /// child tables on PostgreSQL and MySQL.
+    pub fn migration<Metadata: Default + SqlMetadata>(&self, old: &Descriptor<Metadata>, new: &Descriptor<Metadata>) -> Result<Migration, SqlError> {
+        Ok(self.migrate_tables(&self.tables(old)?, &self.tables(new)?))
+    }
+
+    /// Build the migration between two sets of tables, tables are matched by name
+    pub fn migrate_tables(&self, old: &[Table], new: &[Table]) -> Migration {
+        let mut migration = Migration::default();
+        for table in new {
+            match old.iter().find(|candidate| candidate.name == table.name) {
+                Some(old) => self.migrate_table(&mut migration, old, table),
+                None => migration.push(format!("create table {}", table.name), self.create_table(table), false),
+            }
+        }
+        for table in old.iter().rev() {
+            if !new.iter().any(|candidate| candidate.name == table.name) {
+                migration.push(format!("drop table {}", table.name), vec![format!("DROP TABLE {};", self.quote(&table.name))], true);
+            }
+        }
+        migration
+    }
+
+    /// Add the steps changing one table
+    fn migrate_table(&self, migration: &mut Migration, old: &Table, new: &Table) {
+        let changes = ColumnChanges::new(old, new);
+
+        if self.dialect == Dialect::Sqlite {
+            let altered = !changes.removed.is_empty()
+                || changes.added.iter().any(|column| !column.nullable)
+                || changes.kept.iter().any(|(old, new)| old.sql_type != new.sql_type || old.nullable != new.nullable || old.allowed != new.allowed)
+                || old.primary_key != new.primary_key
+                || old.foreign_key != new.foreign_key;
+            if altered {
+                return self.rebuild_table(migration, old, new, &changes)
+            }
+        }
+
+        let table = self.quote(&new.name);
+        let alter = |action: String| format!("ALTER TABLE {table} {action};");
+
+        for index in &old.indexes {
+            if !new.indexes.contains(index) {
+                let statement = match self.dialect {
+                    Dialect::MySql => format!("DROP INDEX {} ON {table};", self.quote(&index.name)),
+                    Dialect::Sqlite | Dialect::Postgres => format!("DROP INDEX {};", self.quote(&index.name)),
+                };
+                migration.push(format!("drop index {}", index.name), vec![statement], false);
+            }
+        }
+        if old.primary_key != new.primary_key && !old.primary_key.is_empty() {
+            let statement = match self.dialect {
+                Dialect::MySql => alter("DROP PRIMARY KEY".to_owned()),
+                Dialect::Sqlite | Dialect::Postgres => alter(format!("DROP CONSTRAINT {}", self.quote(&format!("{}_pkey", new.name)))),
+            };
+            migration.push(format!("drop primary key of {}", new.name), vec![statement], false);
+        }
+
+        for (old_column, column) in &changes.kept {
+            if old_column.name == column.name {
+                continue
+            }
+            let mut statements = vec![alter(format!("RENAME COLUMN {} TO {}", self.quote(&old_column.name), self.quote(&column.name)))];
+            if old_column.allowed.is_some() {
+                let from = self.quote(&check_name(&old.name, &old_column.name));
+                let to = self.quote(&check_name(&new.name, &column.name));
+                match self.dialect {
+                    Dialect::Postgres => statements.push(alter(format!("RENAME CONSTRAINT {from} TO {to}"))),
+                    Dialect::MySql => {
+                        statements.push(alter(format!("DROP CHECK {from}")));
+                        let mut renamed = (*old_column).clone();
+                        renamed.name = column.name.clone();
+                        statements.extend(self.check_constraint(&new.name, &renamed).map(|check| alter(format!("ADD {check}"))));
+                    },
+                    Dialect::Sqlite => {},
+                }
+            }
+            migration.push(format!("rename column {}.{} to {}", new.name, old_column.name, column.name), statements, false);
+        }
+
+        for column in &changes.added {
+            let mut statements = vec![alter(format!("ADD COLUMN {}", self.column_definition(&new.name, column)))];
+            if let (Dialect::Postgres, Some(comment)) = (self.dialect, &column.comment) {
+                statements.push(format!("COMMENT ON COLUMN {table}.{} IS {};", self.quote(&column.name), string_literal(comment)));
+            }
+            let description = if column.nullable {
+                format!("add column {}.{}", new.name, column.name)
+            } else {
+                format!("add column {}.{}, fails if the table has rows since it is NOT NULL without a default", new.name, column.name)
+            };
+            migration.push(description, statements, !column.nullable);
+        }
+
+        for (old_column, column) in &changes.kept {
+            self.migrate_column(migration, new, old_column, column);
+        }
+
+        for column in &changes.removed {
+            migration.push(format!("drop column {}.{}", old.name, column.name), vec![alter(format!("DROP COLUMN {}", self.quote(&column.name)))], true);
+        }
+
+        if old.primary_key != new.primary_key && !new.primary_key.is_empty() {
+            migration.push(format!("set primary key of {}", new.name), vec![alter(format!("ADD PRIMARY KEY ({})", self.column_list(&new.primary_key)))], true);
+        }
+        for index in &new.indexes {
+            if !old.indexes.contains(index) {
+                migration.push(format!("create index {}", index.name), vec![self.create_index(&new.name, index)], index.unique);
+            }
+        }
+
+        if old.comment != new.comment {
+            let comment = new.comment.as_deref().map(string_literal);
+            match self.dialect {
+                Dialect::Postgres => migration.push(format!("comment on table {}", new.name), vec![format!("COMMENT ON TABLE {table} IS {};", comment.unwrap_or_else(|| "NULL".to_owned()))], false),
+                Dialect::MySql => migration.push(format!("comment on table {}", new.name), vec![alter(format!("COMMENT={}", comment.unwrap_or_else(|| "''".to_owned())))], false),
+                Dialect::Sqlite => {},
+            }
+        }
+    }
+
+    /// Add the steps changing the type, nullability, allowed values or comment of a column
+    fn migrate_column(&self, migration: &mut Migration, table: &Table, old: &Column, new: &Column) {
+        let quoted = self.quote(&table.name);
+        let column = self.quote(&new.name);
+        let path = format!("{}.{}", table.name, new.name);
+        let alter = |action: String| format!("ALTER TABLE {quoted} {action};");
+
+        if old.allowed != new.allowed && old.allowed.is_some() {
+            let check = self.quote(&check_name(&table.name, &new.name));
+            let statement = match self.dialect {
+                Dialect::MySql => alter(format!("DROP CHECK {check}")),
+                Dialect::Sqlite | Dialect::Postgres => alter(format!("DROP CONSTRAINT {check}")),
+            };
+            migration.push(format!("drop allowed values of {path}"), vec![statement], false);
+        }
+
+        match self.dialect {
+            Dialect::Postgres => {
+                if old.sql_type != new.sql_type {
+                    migration.push(format!("change type of {path} from {} to {}", old.sql_type, new.sql_type),
+                        vec![alter(format!("ALTER COLUMN {column} TYPE {} USING {column}::{}", new.sql_type, new.sql_type))], true);
+                }
+                if old.nullable && !new.nullable {
+                    migration.push(format!("make {path} NOT NULL, fails if it holds NULL values"), vec![alter(format!("ALTER COLUMN {column} SET NOT NULL"))], true);
+                } else if !old.nullable && new.nullable {
+                    migration.push(format!("allow NULL in {path}"), vec![alter(format!("ALTER COLUMN {column} DROP NOT NULL"))], false);
+                }
+                if old.comment != new.comment {
+                    let comment = new.comment.as_deref().map(string_literal).unwrap_or_else(|| "NULL".to_owned());
+                    migration.push(format!("comment on column {path}"), vec![format!("COMMENT ON COLUMN {quoted}.{column} IS {comment};")], false);
+                }
+            },
+            Dialect::MySql => {
+                if old.sql_type != new.sql_type || old.nullable != new.nullable || old.comment != new.comment {
+                    let mut definition = self.column_spec(new);
+                    if let Some(comment) = &new.comment {
+                        definition += &format!(" COMMENT {}", string_literal(comment));
+                    }
+                    let destructive = old.sql_type != new.sql_type || (old.nullable && !new.nullable);
+                    migration.push(format!("modify column {path}"), vec![alter(format!("MODIFY COLUMN {definition}"))], destructive);
+                }
+            },
+            // every other change rebuilds the table
+            Dialect::Sqlite => {},
+        }
+
+        if old.allowed != new.allowed {
+            if let Some(check) = self.check_constraint(&table.name, new) {
+                migration.push(format!("limit {path} to {}", new.allowed.iter().flatten().cloned().collect::<Vec<_>>().join(", ")), vec![alter(format!("ADD {check}"))], narrows_allowed(old, new));
+            }
+        }
+    }
+
+    /// Add the steps rebuilding a SQLite table, copying the rows of the old table into a new one
+    fn rebuild_table(&self, migration: &mut Migration, old: &Table, new: &Table, changes: &ColumnChanges<'_>) {
+        let copy = self.quote(&format!("{}_new", new.name));
+
+        let targets: Vec<String> = changes.kept.iter().map(|(_, column)| column.name.clone()).collect();
+        let sources: Vec<String> = changes.kept.iter().map(|(column, _)| column.name.clone()).collect();
+
+        // the pragma has no effect inside a transaction so it is changed around it
+        let mut statements = vec!["PRAGMA foreign_keys = OFF;".to_owned(), "BEGIN TRANSACTION;".to_owned()];
+        statements.push(self.create_statement(new, &format!("{}_new", new.name)));
+        statements.push(format!("INSERT INTO {copy} ({}) SELECT {} FROM {};", self.column_list(&targets), self.column_list(&sources), self.quote(&old.name)));
+        statements.push(format!("DROP TABLE {};", self.quote(&old.name)));
+        statements.push(format!("ALTER TABLE {copy} RENAME TO {};", self.quote(&new.name)));
+        statements.extend(new.indexes.iter().map(|index| self.create_index(&new.name, index)));
+        statements.push("PRAGMA foreign_key_check;".to_owned());
+        statements.push("COMMIT;".to_owned());
+        statements.push("PRAGMA foreign_keys = ON;".to_owned());
+
+        let mut reasons = vec![];
+        reasons.extend(changes.removed.iter().map(|column| format!("drops column {}", column.name)));
+        for (old_column, column) in &changes.kept {
+            if old_column.sql_type != column.sql_type {
+                reasons.push(format!("changes type of {} from {} to {}", column.name, old_column.sql_type, column.sql_type));
+            }
+            if old_column.nullable && !column.nullable {
+                reasons.push(format!("makes {} NOT NULL", column.name));
+            }
+            if narrows_allowed(old_column, column) {
+                reasons.push(format!("limits the values of {}", column.name));
+            }
+        }
+        reasons.extend(changes.added.iter().filter(|column| !column.nullable).map(|column| format!("adds NOT NULL column {}", column.name)));
+        if old.primary_key != new.primary_key {
+            reasons.push("changes the primary key".to_owned());
+        }
+
+        let mut description = format!("rebuild table {}", new.name);
+        if !reasons.is_empty() {
+            description += ", ";
+            description += &reasons.join(", ");
+        }
+        let destructive = !reasons.is_empty();
+        migration.push(description, statements, destructive);
+    }
+}
@@ -0,0 +1,415 @@
+//! Generate commented example configuration files from descriptors.
+//!
+//! Every field that can be deserialized is written with its docs and a type hint as comments.
+//! Fields use their default value when one is known, otherwise a placeholder naming the type
+//! or the allowed values. Optional fields, and fields with a default that isn't known, are
+//! written commented out.
+
+use std::borrow::Cow;
+
+use serde_json::Value;
+
+use crate::named::{doc_lines, unalias};
+use crate::{Descriptor, Entry, Kind};
+
+/// File formats templates can be written in
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    /// YAML
+    Yaml,
+    /// TOML
+    Toml,
+    /// JSON5, JSON with comments and trailing commas
+    Json5,
+}
+
+/// Configurable example configuration generator
+#[derive(Debug, Clone)]
+pub struct Template {
+    /// Format written
+    format: Format,
+    /// Known default values, usually a serialized `Default` instance of the config
+    defaults: Option<Value>,
+}
+
+/// A value in the example document
+enum Node {
+    /// A known value
+    Value(Value),
+    /// Text standing in for a value that has to be filled in
+    Placeholder(String),
+    /// The fields of a struct
+    Object(Vec<Field>),
+    /// A list, with an example item
+    List(Box<Node>),
+    /// A map, with a placeholder key and an example value
+    Map(String, Box<Node>),
+}
+
+/// A field in the example document
+struct Field {
+    /// Key of the field
+    key: Cow<'static, str>,
+    /// Comment lines written before the field
+    comments: Vec<String>,
+    /// Value of the field
+    node: Node,
+    /// Is the field written commented out
+    commented: bool,
+}
+
+impl Template {
+    /// Create a generator for a format
+    pub fn new(format: Format) -> Self {
+        Self { format, defaults: None }
+    }
+
+    /// Provide the default values shown for fields, usually a serialized `Default` instance of
+    /// the config. Values are looked up by field label and nested structs use the matching
+    /// nested object.
+    pub fn defaults(mut self, values: Value) -> Self {
+        self.defaults = Some(values);
+        self
+    }
+
+    /// Generate the example file for a config struct
+    pub fn generate<Metadata: Default>(&self, descriptor: &Descriptor<Metadata>) -> String {
+        let mut stack = vec![];
+        let node = node(descriptor, self.defaults.as_ref(), &mut stack);
+        let comment = match self.format {
+            Format::Yaml | Format::Toml => "#",
+            Format::Json5 => "//",
+        };
+
+        let mut output = String::new();
+        let docs = doc_lines(&descriptor.docs);
+        for line in &docs {
+            output += &comment_line(comment, line);
+            output.push('\n');
+        }
+        if !docs.is_empty() {
+            output.push('\n');
+        }
+
+        let fields = match node {
+            Node::Object(fields) => fields,
+            // non struct roots are written as a single field named `value`
+            node => vec![Field { key: "value".into(), comments: vec![], node, commented: false }],
+        };
+        let lines = match self.format {
+            Format::Yaml => yaml_fields(&fields),
+            Format::Toml => toml_table(&[], &fields),
+            Format::Json5 => {
+                let mut lines = vec!["{".to_owned()];
+                lines.extend(json5_fields(&fields).into_iter().map(|line| format!("  {line}")));
+                lines.push("}".to_owned());
+                lines
+            },
+        };
+        for line in lines {
+            output += &line;
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// Format a comment line
+fn comment_line(comment: &str, line: &str) -> String {
+    if line.is_empty() { comment.to_owned() } else { format!("{comment} {line}") }
+}
+
+/// Build the example value for a type, `stack` holds the structs being expanded
+fn node<'a, Metadata: Default>(descriptor: &'a Descriptor<Metadata>, known: Option<&Value>, stack: &mut Vec<&'a str>) -> Node {
+    let descriptor = unalias(descriptor);
+    match (&descriptor.kind, known) {
+        (Kind::Option(kind), _) => node(kind, known, stack),
+        (Kind::Struct { name, children }, _) => {
+            if stack.contains(&name.as_ref()) {
+                return Node::Placeholder(name.to_string())
+            }
+            stack.push(name);
+            let fields = fields(children, known, stack);
+            stack.pop();
+            Node::Object(fields)
+        },
+        (Kind::Sequence(_), Some(Value::Array(items))) if !items.is_empty() => Node::Value(Value::Array(items.clone())),
+        (Kind::Sequence(item), _) => Node::List(Box::new(node(item, None, stack))),
+        (Kind::Mapping(_, _), Some(Value::Object(items))) if !items.is_empty() => Node::Value(Value::Object(items.clone())),
+        (Kind::Mapping(key, value), _) => Node::Map(placeholder(key), Box::new(node(value, None, stack))),
+        (_, Some(value)) if !value.is_null() => Node::Value(value.clone()),
+        _ => Node::Placeholder(placeholder(descriptor)),
+    }
+}
+
+/// Text standing in for a value of a type
+fn placeholder<Metadata: Default>(descriptor: &Descriptor<Metadata>) -> String {
+    match &unalias(descriptor).kind {
+        Kind::Enum { variants, .. } => format!("<{}>", variants.iter().map(|variant| variant.label.as_ref()).collect::<Vec<_>>().join(" | ")),
+        kind => format!("<{}>", kind.type_expression()),
+    }
+}
+
+/// Build the example fields of a struct
+fn fields<'a, Metadata: Default>(children: &'a [Entry<Metadata>], known: Option<&Value>, stack: &mut Vec<&'a str>) -> Vec<Field> {
+    let mut output = vec![];
+    for child in children {
+        if child.skip_deserializing {
+            continue
+        }
+        let value = known.and_then(|known| known.get(child.label.as_ref())).filter(|value| !value.is_null());
+        let mut descriptor = unalias(&child.type_info);
+        let mut optional = false;
+        while let Kind::Option(kind) = &descriptor.kind {
+            optional = true;
+            descriptor = unalias(kind);
+        }
+
+        let mut comments: Vec<String> = doc_lines(&child.docs).iter().map(|line| line.to_string()).collect();
+        let mut hint = match &descriptor.kind {
+            Kind::Enum { variants, .. } => Some(format!("One of: {}", variants.iter().map(|variant| variant.label.as_ref()).collect::<Vec<_>>().join(", "))),
+            Kind::Struct { .. } => None,
+            kind => Some(format!("Type: {}", kind.type_expression())),
+        };
+        let required = !optional && !child.has_default;
+        if required {
+            hint = Some(match hint {
+                Some(hint) => format!("{hint}, required"),
+                None => "Required".to_owned(),
+            });
+        }
+        comments.extend(hint);
+
+        // required fields always get a placeholder, nested structs may still have defaults
+        let value = if child.has_default || matches!(descriptor.kind, Kind::Struct { .. }) { value } else { None };
+        let node = node(descriptor, value, stack);
+        // leaving out a field with a default is safer than writing a placeholder for it
+        let commented = optional || (child.has_default && matches!(node, Node::Placeholder(_) | Node::List(_) | Node::Map(..)));
+        output.push(Field { key: child.label.clone(), comments, node, commented });
+    }
+    output
+}
+
+/// Prefix lines that are written commented out
+fn comment_out(lines: Vec<String>, commented: bool, comment: &str) -> Vec<String> {
+    if commented {
+        lines.into_iter().map(|line| format!("{comment} {line}")).collect()
+    } else {
+        lines
+    }
+}
+
+/// Indent every line
+fn indent(lines: Vec<String>, indent: &str) -> impl Iterator<Item = String> + '_ {
+    lines.into_iter().map(move |line| format!("{indent}{line}"))
+}
+
+/// Render the fields of a struct as YAML lines
+fn yaml_fields(fields: &[Field]) -> Vec<String> {
+    let mut output = vec![];
+    for field in fields {
+        for line in &field.comments {
+            output.push(comment_line("#", line));
+        }
+        let mut lines = yaml_value(&field.key, &field.node);
+        lines = comment_out(lines, field.commented, "#");
+        output.extend(lines);
+    }
+    output
+}
+
+/// Render a YAML key and value
+fn yaml_value(key: &str, node: &Node) -> Vec<String> {
+    let key = key_text(key);
+    match node {
+        Node::Value(value) => vec![format!("{key}: {value}")],
+        Node::Placeholder(text) => vec![format!("{key}: {text}")],
+        Node::Object(fields) if fields.is_empty() => vec![format!("{key}: {{}}")],
+        Node::Object(fields) => {
+            let mut lines = vec![format!("{key}:")];
+            lines.extend(indent(yaml_fields(fields), "  "));
+            lines
+        },
+        Node::List(item) => {
+            let mut lines = vec![format!("{key}:")];
+            match item.as_ref() {
+                Node::Object(fields) => {
+                    lines.push("  -".to_owned());
+                    lines.extend(indent(yaml_fields(fields), "    "));
+                },
+                Node::Value(value) => lines.push(format!("  - {value}")),
+                Node::Placeholder(text) => lines.push(format!("  - {text}")),
+                nested => {
+                    lines.push("  -".to_owned());
+                    lines.extend(indent(yaml_value("", nested).into_iter().skip(1).collect(), "  "));
+                },
+            }
+            lines
+        },
+        Node::Map(placeholder, value) => {
+            let mut lines = vec![format!("{key}:")];
+            lines.extend(indent(yaml_value(placeholder, value), "  "));
+            lines
+        },
+    }
+}
+
+/// Quote a YAML or TOML key unless it only uses the characters both allow in bare keys
+fn key_text(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        key.to_owned()
+    } else {
+        format!("{}", Value::String(key.to_owned()))
+    }
+}
+
+/// Render a value as a TOML inline value
+fn toml_value(value: &Value) -> String {
+    match value {
+        Value::Array(items) => format!("[{}]", items.iter().map(toml_value).collect::<Vec<_>>().join(", ")),
+        Value::Object(items) => {
+            let items: Vec<_> = items.iter().map(|(key, value)| format!("{} = {}", key_text(key), toml_value(value))).collect();
+            if items.is_empty() { "{}".to_owned() } else { format!("{{ {} }}", items.join(", ")) }
+        },
+        // TOML has no null, an empty string is the closest stand in
+        Value::Null => "\"\"".to_owned(),
+        value => value.to_string(),
+    }
+}
+
+/// Is a node written as a table rather than a key value pair
+fn toml_is_table(node: &Node) -> bool {
+    match node {
+        Node::Object(_) | Node::Map(..) => true,
+        Node::List(item) => matches!(item.as_ref(), Node::Object(_)),
+        _ => false,
+    }
+}
+
+/// Render the fields of a struct as a TOML table, key value pairs first followed by tables
+fn toml_table(path: &[String], fields: &[Field]) -> Vec<String> {
+    let mut output = vec![];
+    for field in fields.iter().filter(|field| !toml_is_table(&field.node)) {
+        for line in &field.comments {
+            output.push(comment_line("#", line));
+        }
+        let key = key_text(&field.key);
+        let line = match &field.node {
+            Node::Value(value) => format!("{key} = {}", toml_value(value)),
+            Node::Placeholder(text) => format!("{key} = {}", Value::String(text.clone())),
+            Node::List(item) => match item.as_ref() {
+                Node::Value(value) => format!("{key} = [{}]", toml_value(value)),
+                Node::Placeholder(text) => format!("{key} = [{}]", Value::String(text.clone())),
+                _ => format!("{key} = []"),
+            },
+            _ => continue,
+        };
+        output.extend(comment_out(vec![line], field.commented, "#"));
+    }
+
+    for field in fields.iter().filter(|field| toml_is_table(&field.node)) {
+        let mut path = path.to_vec();
+        path.push(key_text(&field.key));
+        let mut lines = vec![String::new()];
+        lines.extend(field.comments.iter().map(|line| comment_line("#", line)));
+        let mut body = vec![];
+        match &field.node {
+            Node::Object(fields) => {
+                body.push(format!("[{}]", path.join(".")));
+                body.extend(toml_table(&path, fields));
+            },
+            Node::List(item) => if let Node::Object(fields) = item.as_ref() {
+                body.push(format!("[[{}]]", path.join(".")));
+                body.extend(toml_table(&path, fields));
+            },
+            Node::Map(placeholder, value) => {
+                match value.as_ref() {
+                    Node::Object(fields) => {
+                        let mut entry_path = path.clone();
+                        entry_path.push(key_text(placeholder));
+                        body.push(format!("[{}]", entry_path.join(".")));
+                        body.extend(toml_table(&entry_path, fields));
+                    },
+                    value => {
+                        body.push(format!("[{}]", path.join(".")));
+                        let value = match value {
+                            Node::Value(value) => toml_value(value),
+                            Node::Placeholder(text) => Value::String(text.clone()).to_string(),
+                            _ => "{}".to_owned(),
+                        };
+                        body.push(format!("{} = {value}", key_text(placeholder)));
+                    },
+                }
+            },
+            _ => {}
+        }
+        lines.extend(comment_out(body, field.commented, "#"));
+        output.extend(lines);
+    }
+    output
+}
+
+/// Quote a JSON5 key when it isn't an identifier
+fn json5_key(key: &str) -> String {
+    let mut chars = key.chars();
+    let identifier = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier { key.to_owned() } else { Value::String(key.to_owned()).to_string() }
+}
+
+/// Render the fields of a struct as JSON5 lines
+fn json5_fields(fields: &[Field]) -> Vec<String> {
+    let mut output = vec![];
+    for field in fields {
+        for line in &field.comments {
+            output.push(comment_line("//", line));
+        }
+        let mut lines = json5_value(&field.node);
+        if let Some(first) = lines.first_mut() {
+            *first = format!("{}: {first}", json5_key(&field.key));
+        }
+        if let Some(last) = lines.last_mut() {
+            last.push(',');
+        }
+        output.extend(comment_out(lines, field.commented, "//"));
+    }
+    output
+}
+
+/// Render a JSON5 value
+fn json5_value(node: &Node) -> Vec<String> {
+    match node {
+        Node::Value(value) => vec![value.to_string()],
+        Node::Placeholder(text) => vec![Value::String(text.clone()).to_string()],
+        Node::Object(fields) if fields.is_empty() => vec!["{}".to_owned()],
+        Node::Object(fields) => {
+            let mut lines = vec!["{".to_owned()];
+            lines.extend(indent(json5_fields(fields), "  "));
+            lines.push("}".to_owned());
+            lines
+        },
+        Node::List(item) => {
+            let mut lines = vec!["[".to_owned()];
+            let mut item = json5_value(item);
+            if let Some(last) = item.last_mut() {
+                last.push(',');
+            }
+            lines.extend(indent(item, "  "));
+            lines.push("]".to_owned());
+            lines
+        },
+        Node::Map(placeholder, value) => {
+            let mut lines = vec!["{".to_owned()];
+            let mut entry = json5_value(value);
+            if let Some(first) = entry.first_mut() {
+                *first = format!("{}: {first}", json5_key(placeholder));
+            }
+            if let Some(last) = entry.last_mut() {
+                last.push(',');
+            }
+            lines.extend(indent(entry, "  "));
+            lines.push("}".to_owned());
+            lines
+        },
+    }
+}
@@ -0,0 +1,134 @@
+//! Generate TypeScript declarations from descriptors.
+//!
+//! Structs become interfaces, enums become unions of string literals and aliased types become
+//! type aliases. Declarations are sorted by name so the output can be checked in.
+
+use std::fmt::Write;
+
+use crate::named::{doc_lines, named_types, string_literal};
+use crate::{Descriptor, Kind};
+
+/// Configurable TypeScript declaration generator
+#[derive(Debug, Clone)]
+pub struct TypeScript {
+    /// Type used for 64 and 128 bit integers
+    big_integer: String,
+    /// Indentation used inside interfaces
+    indent: String,
+}
+
+impl Default for TypeScript {
+    fn default() -> Self {
+        Self {
+            big_integer: "number".to_owned(),
+            indent: "    ".to_owned(),
+        }
+    }
+}
+
+impl TypeScript {
+    /// Create a generator with the default settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the type used for 64 and 128 bit integers, `number` loses precision above 2^53 so
+    /// `string` or `bigint` may be used instead depending on how values are serialized
+    pub fn big_integer_type(mut self, name: &str) -> Self {
+        self.big_integer = name.to_owned();
+        self
+    }
+
+    /// Set the indentation used for interface members
+    pub fn indent(mut self, indent: &str) -> Self {
+        self.indent = indent.to_owned();
+        self
+    }
+
+    /// Generate declarations for every named type used by the given descriptors
+    pub fn generate<'a, Metadata: Default + 'a>(&self, roots: impl IntoIterator<Item = &'a Descriptor<Metadata>>) -> String {
+        let mut output = String::new();
+        for (name, descriptor) in named_types(roots) {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            write_docs(&mut output, "", &doc_lines(&descriptor.docs));
+            match &descriptor.kind {
+                Kind::Struct { children, .. } => {
+                    let _ = writeln!(output, "export interface {name} {{");
+                    for child in children {
+                        if child.skip_serializing && child.skip_deserializing {
+                            continue
+                        }
+                        write_docs(&mut output, &self.indent, &doc_lines(&child.docs));
+                        let (optional, kind) = match &child.type_info.kind {
+                            Kind::Option(inner) => ("?", format!("{} | null", self.type_expression(inner, false))),
+                            _ => ("", self.type_expression(&child.type_info, false)),
+                        };
+                        let _ = writeln!(output, "{}{}{optional}: {kind};", self.indent, property_name(&child.label));
+                    }
+                    output.push_str("}\n");
+                },
+                Kind::Enum { variants, .. } => {
+                    let labels: Vec<_> = variants.iter().map(|variant| string_literal(&variant.label)).collect();
+                    let labels = if labels.is_empty() { "never".to_owned() } else { labels.join(" | ") };
+                    let _ = writeln!(output, "export type {name} = {labels};");
+                },
+                Kind::Aliased { kind, .. } => {
+                    let _ = writeln!(output, "export type {name} = {};", self.type_expression(kind, false));
+                },
+                _ => {}
+            }
+        }
+        output
+    }
+
+    /// Render the TypeScript type for a value, `nested` is set when a union needs parentheses
+    fn type_expression<Metadata: Default>(&self, descriptor: &Descriptor<Metadata>, nested: bool) -> String {
+        match &descriptor.kind {
+            Kind::Struct { name, .. } |
+            Kind::Aliased { name, .. } |
+            Kind::Enum { name, .. } => name.to_string(),
+            Kind::Sequence(kind) => format!("{}[]", self.type_expression(kind, true)),
+            Kind::Option(kind) => {
+                let union = format!("{} | null", self.type_expression(kind, false));
+                if nested { format!("({union})") } else { union }
+            },
+            Kind::Mapping(key, value) => format!("Record<{}, {}>", self.type_expression(key, false), self.type_expression(value, false)),
+            Kind::DateTime | Kind::String => "string".to_owned(),
+            Kind::U128 | Kind::I128 | Kind::U64 | Kind::I64 => self.big_integer.clone(),
+            Kind::U32 | Kind::I32 | Kind::U16 | Kind::I16 | Kind::U8 | Kind::I8 | Kind::F64 | Kind::F32 => "number".to_owned(),
+            Kind::Bool => "boolean".to_owned(),
+            Kind::JSON | Kind::Any => "unknown".to_owned(),
+        }
+    }
+}
+
+/// Write doc strings as a JSDoc comment
+fn write_docs(output: &mut String, indent: &str, lines: &[&str]) {
+    match lines {
+        [] => {},
+        [line] => {
+            let _ = writeln!(output, "{indent}/** {} */", line.replace("*/", "*\\/"));
+        },
+        lines => {
+            let _ = writeln!(output, "{indent}/**");
+            for line in lines {
+                if line.is_empty() {
+                    let _ = writeln!(output, "{indent} *");
+                } else {
+                    let _ = writeln!(output, "{indent} * {}", line.replace("*/", "*\\/"));
+                }
+            }
+            let _ = writeln!(output, "{indent} */");
+        }
+    }
+}
+
+/// Property names that aren't valid identifiers need to be quoted
+fn property_name(label: &str) -> String {
+    let mut chars = label.chars();
+    let valid = chars.next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_' || first == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if valid { label.to_owned() } else { string_literal(label) }
+}
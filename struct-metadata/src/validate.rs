@@ -0,0 +1,336 @@
+//! Validation of JSON values against descriptors.
+//!
+//! Every violation found is collected along with the JSON path it was found at, rather than
+//! stopping at the first problem the way deserializing does. Extra constraints can be checked
+//! by a hook that reads the metadata of each type and field.
+
+use core::fmt;
+
+use serde_json::Value;
+
+use crate::named::{child_path, unalias};
+use crate::{Descriptor, Entry, Kind};
+
+/// Function used to check metadata driven constraints on a value
+type Hook<'a, Metadata> = dyn Fn(&Metadata, &Value) -> Vec<String> + 'a;
+
+/// A problem found validating a value
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Violation {
+    /// JSON path to the invalid value, `$` is the root
+    pub path: String,
+    /// What is wrong with the value
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+/// Description of a single problem with a value
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ViolationKind {
+    /// The value has the wrong JSON type
+    WrongType {
+        /// Type expected at this location
+        expected: String,
+        /// JSON type found
+        found: &'static str,
+    },
+    /// A field that has no default is missing
+    MissingField {
+        /// Label of the missing field
+        field: String,
+    },
+    /// An object has a key that isn't the label or alias of a field
+    UnknownField {
+        /// The unexpected key
+        field: String,
+    },
+    /// A string isn't the label or alias of an enum variant
+    UnknownVariant {
+        /// The unexpected value
+        value: String,
+        /// Labels of the variants
+        expected: Vec<String>,
+    },
+    /// A number doesn't fit in the integer or float type
+    OutOfRange {
+        /// The number found
+        value: String,
+        /// Type the number must fit in
+        expected: String,
+    },
+    /// A key of a map isn't valid for the key type
+    InvalidKey {
+        /// The invalid key
+        key: String,
+        /// What is wrong with the key
+        problem: Box<ViolationKind>,
+    },
+    /// A value couldn't be parsed, such as a timestamp
+    InvalidFormat {
+        /// Why the value is invalid
+        message: String,
+    },
+    /// A constraint checked by a validation hook failed
+    Constraint {
+        /// Message returned by the hook
+        message: String,
+    },
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViolationKind::WrongType { expected, found } => write!(f, "expected {expected}, found {found}"),
+            ViolationKind::MissingField { field } => write!(f, "missing field {field}"),
+            ViolationKind::UnknownField { field } => write!(f, "unknown field {field}"),
+            ViolationKind::UnknownVariant { value, expected } => write!(f, "unknown variant {value}, expected one of {}", expected.join(", ")),
+            ViolationKind::OutOfRange { value, expected } => write!(f, "{value} is out of range for {expected}"),
+            ViolationKind::InvalidKey { key, problem } => write!(f, "invalid key {key}: {problem}"),
+            ViolationKind::InvalidFormat { message } => f.write_str(message),
+            ViolationKind::Constraint { message } => f.write_str(message),
+        }
+    }
+}
+
+/// Configurable validator for a descriptor
+pub struct Validator<'a, Metadata: Default> {
+    /// Type values are checked against
+    descriptor: &'a Descriptor<Metadata>,
+    /// User function called for each type and field with a value
+    hook: Option<Box<Hook<'a, Metadata>>>,
+}
+
+impl<'a, Metadata: Default> Validator<'a, Metadata> {
+    /// Create a validator for a type
+    pub fn new(descriptor: &'a Descriptor<Metadata>) -> Self {
+        Self { descriptor, hook: None }
+    }
+
+    /// Add a function that checks constraints based on metadata.
+    ///
+    /// The hook is called with the type metadata and the value for every type, and with the
+    /// field metadata and the value for every field present. Each message it returns is
+    /// reported as a constraint violation.
+    pub fn with_hook(mut self, hook: impl Fn(&Metadata, &Value) -> Vec<String> + 'a) -> Self {
+        self.hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Check a value, returning every violation found
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        let mut output = vec![];
+        self.check(&mut output, "$".to_owned(), self.descriptor, value);
+        output
+    }
+
+    /// Run the hook for a piece of metadata
+    fn hook(&self, output: &mut Vec<Violation>, path: &str, metadata: &Metadata, value: &Value) {
+        if let Some(hook) = &self.hook {
+            for message in hook(metadata, value) {
+                output.push(Violation { path: path.to_owned(), kind: ViolationKind::Constraint { message } });
+            }
+        }
+    }
+
+    /// Check a value against a type
+    fn check(&self, output: &mut Vec<Violation>, path: String, descriptor: &Descriptor<Metadata>, value: &Value) {
+        self.hook(output, &path, &descriptor.metadata, value);
+        let wrong_type = |output: &mut Vec<Violation>, path: String| output.push(Violation {
+            path,
+            kind: ViolationKind::WrongType { expected: descriptor.kind.type_expression(), found: json_type(value) },
+        });
+
+        match &descriptor.kind {
+            Kind::Struct { children, .. } => match value {
+                Value::Object(items) => self.check_fields(output, &path, children, items),
+                _ => wrong_type(output, path),
+            },
+            Kind::Aliased { kind, .. } => self.check(output, path, kind, value),
+            Kind::Enum { variants, .. } => match value {
+                Value::String(label) => {
+                    let known = variants.iter().any(|variant| variant.label == *label || variant.aliases.iter().any(|alias| alias == label));
+                    if !known {
+                        output.push(Violation { path, kind: ViolationKind::UnknownVariant {
+                            value: label.clone(),
+                            expected: variants.iter().map(|variant| variant.label.to_string()).collect(),
+                        }});
+                    }
+                },
+                _ => wrong_type(output, path),
+            },
+            Kind::Sequence(kind) => match value {
+                Value::Array(items) => {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check(output, format!("{path}[{index}]"), kind, item);
+                    }
+                },
+                _ => wrong_type(output, path),
+            },
+            Kind::Option(kind) => {
+                if !value.is_null() {
+                    self.check(output, path, kind, value);
+                }
+            },
+            Kind::Mapping(key, kind) => match value {
+                Value::Object(items) => {
+                    for (name, item) in items {
+                        // object keys are always strings, numeric keys are written as text
+                        let numeric = integer_range(&unalias(key).kind).is_some() && (name.parse::<i128>().is_ok() || name.parse::<u128>().is_ok());
+                        let key_value = match numeric {
+                            true => serde_json::from_str(name).unwrap_or_else(|_| Value::String(name.clone())),
+                            false => Value::String(name.clone()),
+                        };
+                        let mut problems = vec![];
+                        self.check(&mut problems, path.clone(), key, &key_value);
+                        output.extend(problems.into_iter().map(|problem| Violation {
+                            path: path.clone(),
+                            kind: ViolationKind::InvalidKey { key: name.clone(), problem: Box::new(problem.kind) },
+                        }));
+                        self.check(output, json_path(&path, name), kind, item);
+                    }
+                },
+                _ => wrong_type(output, path),
+            },
+            Kind::DateTime => match value {
+                #[cfg(feature = "chrono")]
+                Value::String(text) => {
+                    if let Err(error) = chrono::DateTime::parse_from_rfc3339(text) {
+                        output.push(Violation { path, kind: ViolationKind::InvalidFormat { message: format!("invalid timestamp: {error}") } });
+                    }
+                },
+                #[cfg(not(feature = "chrono"))]
+                Value::String(_) => {},
+                _ => wrong_type(output, path),
+            },
+            Kind::String => if !value.is_string() {
+                wrong_type(output, path)
+            },
+            Kind::Bool => if !value.is_boolean() {
+                wrong_type(output, path)
+            },
+            Kind::F64 => if !value.is_number() {
+                wrong_type(output, path)
+            },
+            Kind::F32 => match value.as_f64() {
+                Some(number) if number.is_finite() && number.abs() > f32::MAX as f64 => output.push(Violation {
+                    path,
+                    kind: ViolationKind::OutOfRange { value: value.to_string(), expected: "f32".to_owned() },
+                }),
+                Some(_) => {},
+                None => wrong_type(output, path),
+            },
+            Kind::U128 | Kind::I128 | Kind::U64 | Kind::I64 | Kind::U32 | Kind::I32 | Kind::U16 | Kind::I16 | Kind::U8 | Kind::I8 => {
+                let Some((low, high)) = integer_range(&descriptor.kind) else { return };
+                let wide = matches!(descriptor.kind, Kind::U128 | Kind::I128);
+                // integers beyond 64 bits are parsed as floats, which can still be range checked
+                let in_range = match (value.as_i64(), value.as_u64(), value.as_f64()) {
+                    (Some(number), _, _) => Some(i128::from(number) >= low && (number < 0 || number as u128 <= high)),
+                    (None, Some(number), _) => Some(u128::from(number) <= high),
+                    (None, None, Some(number)) if number.fract() == 0.0 => match float_in_range(number, low, high) {
+                        true if !wide => None,
+                        in_range => Some(in_range),
+                    },
+                    _ => None,
+                };
+                match in_range {
+                    Some(true) => {},
+                    Some(false) => output.push(Violation {
+                        path,
+                        kind: ViolationKind::OutOfRange { value: value.to_string(), expected: descriptor.kind.type_expression() },
+                    }),
+                    None => wrong_type(output, path),
+                }
+            },
+            Kind::JSON | Kind::Any => {},
+        }
+    }
+
+    /// Check the fields of an object against the fields of a struct
+    fn check_fields(&self, output: &mut Vec<Violation>, path: &str, children: &[Entry<Metadata>], items: &serde_json::Map<String, Value>) {
+        for child in children {
+            if child.skip_deserializing {
+                continue
+            }
+            let found = items.iter().find(|(key, _)| child.label == **key || child.aliases.iter().any(|alias| alias == *key));
+            match found {
+                Some((key, value)) => {
+                    let path = json_path(path, key);
+                    self.hook(output, &path, &child.metadata, value);
+                    self.check(output, path, &child.type_info, value);
+                },
+                None => if !child.has_default && !matches!(unalias(&child.type_info).kind, Kind::Option(_)) {
+                    output.push(Violation { path: path.to_owned(), kind: ViolationKind::MissingField { field: child.label.to_string() } });
+                },
+            }
+        }
+        for key in items.keys() {
+            let known = children.iter().any(|child| child.label == *key || child.aliases.iter().any(|alias| alias == key));
+            if !known {
+                output.push(Violation { path: json_path(path, key), kind: ViolationKind::UnknownField { field: key.clone() } });
+            }
+        }
+    }
+}
+
+impl<Metadata: Default> Descriptor<Metadata> {
+    /// Check a JSON value against this type, returning every violation found
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        Validator::new(self).validate(value)
+    }
+
+    /// Build a validator for this type that can be configured with a hook
+    pub fn validator(&self) -> Validator<'_, Metadata> {
+        Validator::new(self)
+    }
+}
+
+/// Smallest and largest values of an integer kind
+fn integer_range<Metadata: Default>(kind: &Kind<Metadata>) -> Option<(i128, u128)> {
+    Some(match kind {
+        Kind::U128 => (0, u128::MAX),
+        Kind::I128 => (i128::MIN, i128::MAX as u128),
+        Kind::U64 => (0, u64::MAX as u128),
+        Kind::I64 => (i64::MIN as i128, i64::MAX as u128),
+        Kind::U32 => (0, u32::MAX as u128),
+        Kind::I32 => (i32::MIN as i128, i32::MAX as u128),
+        Kind::U16 => (0, u16::MAX as u128),
+        Kind::I16 => (i16::MIN as i128, i16::MAX as u128),
+        Kind::U8 => (0, u8::MAX as u128),
+        Kind::I8 => (i8::MIN as i128, i8::MAX as u128),
+        _ => return None,
+    })
+}
+
+/// Check a whole number held as a float against an integer range without rounding the bounds
+fn float_in_range(number: f64, low: i128, high: u128) -> bool {
+    // 2^127, conversions beyond it saturate
+    const LIMIT: f64 = 170141183460469231731687303715884105728.0;
+    if number < 0.0 {
+        number >= -LIMIT && number as i128 >= low
+    } else {
+        number < 2.0 * LIMIT && number as u128 <= high
+    }
+}
+
+/// Name of the JSON type of a value
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Extend a JSON path with an object key, keys that aren't plain names are written in brackets
+fn json_path(path: &str, key: &str) -> String {
+    let plain = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain { child_path(path, key) } else { format!("{path}[{}]", Value::String(key.to_owned())) }
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "arrow")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_schema::{DataType, Field, Fields, Schema, TimeUnit};
+use pretty_assertions::assert_eq;
+use struct_metadata::Described;
+
+
+#[derive(Described)]
+#[metadata(table: "events")]
+#[allow(dead_code)]
+struct Event {
+    #[metadata(unit: "ms")]
+    duration: u32,
+    source: Option<String>,
+    tags: Vec<Option<String>>,
+    counts: HashMap<String, i64>,
+    location: Location,
+    kind: Kind,
+    time: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Location {
+    lat: f64,
+    lon: f64,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum Kind {
+    Created,
+    Deleted,
+}
+
+#[test]
+fn schema() {
+    let entries = Fields::from(vec![
+        Field::new("keys", DataType::Utf8, false),
+        Field::new("values", DataType::Int64, false),
+    ]);
+    let location = Fields::from(vec![
+        Field::new("lat", DataType::Float64, false),
+        Field::new("lon", DataType::Float64, false),
+    ]);
+
+    assert_eq!(Event::metadata().arrow_schema().unwrap(), Schema::new_with_metadata(vec![
+        Field::new("duration", DataType::UInt32, false).with_metadata([("unit".to_owned(), "ms".to_owned())].into()),
+        Field::new("source", DataType::Utf8, true),
+        Field::new("tags", DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true))), false),
+        Field::new("counts", DataType::Map(Arc::new(Field::new("entries", DataType::Struct(entries), false)), false), false),
+        Field::new("location", DataType::Struct(location), false),
+        Field::new("kind", DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)), false),
+        Field::new("time", DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())), false),
+    ], [("table".to_owned(), "events".to_owned())].into()));
+
+    assert!(Kind::metadata().arrow_schema().is_err());
+}
@@ -0,0 +1,157 @@
+#![cfg(feature = "avro")]
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use struct_metadata::avro::{compatibility, resolution_errors, Avro, AvroError, ResolutionError};
+use struct_metadata::{Compatibility, Described};
+
+
+/// An observed event
+#[derive(Described, serde::Serialize, Default)]
+#[allow(dead_code)]
+struct Event {
+    /// Event identifier
+    #[serde(alias = "event_id")]
+    id: String,
+    #[serde(default)]
+    count: u32,
+    #[serde(default)]
+    source: Option<String>,
+    tags: Vec<Tag>,
+    kind: Kind,
+}
+
+#[derive(Described, serde::Serialize, Default)]
+#[allow(dead_code)]
+struct Tag {
+    name: String,
+}
+
+#[derive(Described, serde::Serialize, Default)]
+#[allow(dead_code)]
+enum Kind {
+    #[default]
+    Created,
+    Deleted,
+}
+
+#[test]
+fn schema() {
+    let defaults = json!({"count": 5, "source": "sensor"});
+    assert_eq!(Avro::new().namespace("events").defaults(defaults).schema(&Event::metadata()).unwrap(), json!({
+        "type": "record",
+        "name": "Event",
+        "namespace": "events",
+        "doc": "An observed event",
+        "fields": [
+            {"name": "id", "type": "string", "doc": "Event identifier", "aliases": ["event_id"]},
+            {"name": "count", "type": "long", "default": 5},
+            {"name": "source", "type": ["string", "null"], "default": "sensor"},
+            {"name": "tags", "type": {"type": "array", "items": {
+                "type": "record",
+                "name": "Tag",
+                "fields": [{"name": "name", "type": "string"}],
+            }}},
+            {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["Created", "Deleted"]}},
+        ]
+    }));
+
+    let schema = Event::metadata().avro_schema().unwrap();
+    assert_eq!(schema["fields"][1]["default"], json!(0));
+    assert_eq!(schema["fields"][2], json!({"name": "source", "type": ["null", "string"], "default": null}));
+}
+
+#[test]
+fn resolution() {
+    let old = json!({"type": "record", "name": "Event", "fields": [
+        {"name": "event_id", "type": "string"},
+        {"name": "count", "type": "int"},
+        {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["Created"]}},
+    ]});
+    let new = Event::metadata().avro_schema().unwrap();
+
+    // renamed through an alias, widened and with a new enum symbol, but tags has no default
+    assert_eq!(resolution_errors(&old, &new), vec![ResolutionError {
+        path: "tags".to_owned(),
+        message: "missing from written data and has no default".to_owned(),
+    }]);
+    assert_eq!(resolution_errors(&new, &old), vec![
+        ResolutionError { path: "event_id".to_owned(), message: "missing from written data and has no default".to_owned() },
+        ResolutionError { path: "count".to_owned(), message: "long can't be read as int".to_owned() },
+        ResolutionError { path: "kind".to_owned(), message: "symbol \"Deleted\" is unknown to the reader".to_owned() },
+    ]);
+    assert_eq!(compatibility(&old, &new), Compatibility::None);
+
+    let added = json!({"type": "record", "name": "Tag", "fields": [
+        {"name": "name", "type": "string"},
+        {"name": "score", "type": ["null", "double"], "default": null},
+    ]});
+    assert_eq!(compatibility(&Tag::metadata().avro_schema().unwrap(), &added), Compatibility::Full);
+    assert_eq!(compatibility(&json!("int"), &json!(["null", "long"])), Compatibility::Backward);
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Counter(u64);
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Totals {
+    #[serde(default)]
+    bytes: u64,
+    counter: Counter,
+}
+
+#[test]
+fn wide_integers() {
+    // u64 values don't all fit in a long
+    assert_eq!(Avro::new().defaults(json!({"bytes": 7})).schema(&Totals::metadata()).unwrap(), json!({
+        "type": "record",
+        "name": "Totals",
+        "fields": [
+            {"name": "bytes", "type": "string", "default": "7"},
+            {"name": "counter", "type": "string"},
+        ]
+    }));
+    assert_eq!(Totals::metadata().avro_schema().unwrap()["fields"][0]["default"], json!("0"));
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Upload {
+    #[serde(rename = "mime-type", alias = "content-type", alias = "mime_type")]
+    mime_type: String,
+    state: UploadState,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum UploadState {
+    #[serde(rename = "in-progress")]
+    InProgress,
+    #[serde(rename = "2xx")]
+    Done,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Clashing {
+    #[serde(rename = "file-name")]
+    file: String,
+    file_name: String,
+}
+
+#[test]
+fn sanitized_names() {
+    assert_eq!(Upload::metadata().avro_schema().unwrap(), json!({
+        "type": "record",
+        "name": "Upload",
+        "fields": [
+            {"name": "mime_type", "type": "string", "aliases": ["content_type"]},
+            {"name": "state", "type": {"type": "enum", "name": "UploadState", "symbols": ["in_progress", "_2xx"]}},
+        ]
+    }));
+    let error = Clashing::metadata().avro_schema().unwrap_err();
+    assert_eq!(error, AvroError::NameCollision { name: "Clashing".to_owned(), first: "file-name".to_owned(), second: "file_name".to_owned(), written: "file_name".to_owned() });
+    assert_eq!(error.to_string(), "Clashing: file-name and file_name are both written as file_name");
+}
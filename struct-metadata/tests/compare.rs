@@ -0,0 +1,189 @@
+#![cfg(test)]
+
+use pretty_assertions::assert_eq;
+use struct_metadata::{assert_descriptor_eq, CompareOptions, Described, Difference, Kind};
+
+
+/// An order placed by a customer
+#[derive(Described)]
+#[metadata(table: "orders")]
+#[allow(dead_code)]
+struct Order {
+    /// Order number
+    #[serde(alias = "number")]
+    id: u64,
+    items: Vec<Item>,
+    #[serde(default)]
+    note: Option<String>,
+    state: State,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Item {
+    sku: String,
+    quantity: u32,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum State {
+    Open,
+    Shipped,
+}
+
+mod changed {
+    use struct_metadata::Described;
+
+    /// An order
+    #[derive(Described)]
+    #[metadata(table: "orders")]
+    #[allow(dead_code)]
+    pub struct Order {
+        /// Order number
+        pub id: u64,
+        pub items: Vec<Item>,
+        pub note: Option<String>,
+        pub state: State,
+        pub total: f64,
+    }
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub struct Item {
+        pub sku: String,
+        pub quantity: i32,
+    }
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub enum State {
+        Open,
+        Delivered,
+    }
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub enum Reordered {
+        Shipped,
+        Open,
+    }
+
+    #[derive(Described)]
+    #[metadata(table: "orders", schema: "shop")]
+    #[allow(dead_code)]
+    pub struct Tagged {
+        pub id: u64,
+    }
+
+    #[derive(Described)]
+    #[metadata(table: "order", schema: "shop")]
+    #[allow(dead_code)]
+    pub struct Retagged {
+        pub id: u64,
+    }
+
+    /// Order number
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub struct Aliased {
+        /// Order number
+        #[serde(alias = "number")]
+        pub id: u64,
+    }
+
+    /// Order number
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub struct Plain {
+        /// Different docs
+        pub id: u64,
+    }
+}
+
+fn difference(path: &str, expected: &str, actual: &str) -> Difference {
+    Difference { path: path.to_owned(), expected: expected.to_owned(), actual: actual.to_owned() }
+}
+
+#[test]
+fn equal() {
+    assert_eq!(Order::metadata().compare(&Order::metadata()), vec![]);
+    assert_descriptor_eq!(Order::metadata(), Order::metadata());
+}
+
+#[test]
+fn differences() {
+    assert_eq!(Order::metadata().compare(&changed::Order::metadata()), vec![
+        difference("docs", "Some([\"An order placed by a customer\"])", "Some([\"An order\"])"),
+        difference("id.aliases", "[\"number\"]", "[]"),
+        difference("items.[].quantity.kind", "u32", "i32"),
+        difference("note.default", "true", "false"),
+        difference("state.variants", "Shipped", "(missing)"),
+        difference("state.variants", "(missing)", "Delivered"),
+        difference("fields", "(missing)", "total"),
+    ]);
+
+    let options = CompareOptions::default().ignore_docs().ignore_aliases().limit(2);
+    assert_eq!(Order::metadata().compare_with(&changed::Order::metadata(), options), vec![
+        difference("items.[].quantity.kind", "u32", "i32"),
+        difference("note.default", "true", "false"),
+    ]);
+    assert_eq!(Order::metadata().compare_with(&changed::Order::metadata(), CompareOptions::default().limit(0)).len(), 7);
+    assert_eq!(difference("a.b", "u32", "i32").to_string(), "a.b: u32 vs i32");
+}
+
+#[test]
+fn aliases_are_configurable() {
+    // Entry equality doesn't look at aliases, comparisons do unless told otherwise
+    let (Kind::Struct { children: aliased, .. }, Kind::Struct { children: plain, .. }) = (changed::Aliased::metadata().kind, changed::Plain::metadata().kind) else { panic!() };
+    assert_eq!(aliased[0].type_info, plain[0].type_info);
+    assert_eq!(aliased[0].aliases, &["id", "number"]);
+    assert_eq!(plain[0].aliases, &["id"]);
+    let differences = changed::Aliased::metadata().compare_with(&changed::Plain::metadata(), CompareOptions::default().ignore_docs());
+    assert_eq!(differences, vec![
+        difference("name", "Aliased", "Plain"),
+        difference("id.aliases", "[\"number\"]", "[]"),
+    ]);
+}
+
+#[test]
+fn variant_order() {
+    let differences = State::metadata().compare_with(&changed::Reordered::metadata(), CompareOptions::default().ignore_docs());
+    assert_eq!(differences, vec![
+        difference("name", "State", "Reordered"),
+        difference("variants", "[\"Open\", \"Shipped\"]", "[\"Shipped\", \"Open\"]"),
+    ]);
+}
+
+#[test]
+fn metadata_is_sorted() {
+    let differences = changed::Tagged::metadata().compare_with(&changed::Retagged::metadata(), CompareOptions::default());
+    assert_eq!(differences, vec![
+        difference("metadata", r#"{"schema": "\"shop\"", "table": "\"orders\""}"#, r#"{"schema": "\"shop\"", "table": "\"order\""}"#),
+        difference("name", "Tagged", "Retagged"),
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "descriptors are not equal (expected vs actual):\n    items.[].quantity.kind: u32 vs i32")]
+fn assertion_message() {
+    assert_descriptor_eq!(Order::metadata(), changed::Order::metadata(), CompareOptions::default().ignore_docs().ignore_aliases());
+}
+
+/// Message of the panic raised by a failed assertion
+fn panic_message(check: impl FnOnce() + std::panic::UnwindSafe) -> String {
+    let error = std::panic::catch_unwind(check).expect_err("assertion should fail");
+    error.downcast_ref::<String>().cloned().unwrap_or_default()
+}
+
+#[test]
+fn truncated_message() {
+    let options = CompareOptions::default().ignore_docs().ignore_aliases();
+    assert_eq!(panic_message(|| assert_descriptor_eq!(Order::metadata(), changed::Order::metadata(), options.limit(1))),
+        "descriptors are not equal (expected vs actual):\n    items.[].quantity.kind: u32 vs i32\n    ...");
+    // every difference fits within the limit so nothing is marked as left out
+    assert_eq!(panic_message(|| assert_descriptor_eq!(Order::metadata(), changed::Order::metadata(), options.limit(5))),
+        "descriptors are not equal (expected vs actual):\n    items.[].quantity.kind: u32 vs i32\n    note.default: true vs false\n    state.variants: Shipped vs (missing)\n    state.variants: (missing) vs Delivered\n    fields: (missing) vs total");
+    assert_eq!(panic_message(|| assert_descriptor_eq!(Order::metadata(), changed::Order::metadata(), options.limit(0))).lines().count(), 6);
+    assert_eq!(panic_message(|| assert_descriptor_eq!(Order::metadata(), changed::Order::metadata(), options.limit(usize::MAX))).lines().count(), 6);
+}
@@ -20,14 +20,14 @@ struct OptionVec {
 #[test]
 fn option_vec() {
     assert_eq!(OptionVec::metadata(), Descriptor {
-        docs: Some(vec!["non trivial metadata structs"]),
+        docs: Some(vec!["non trivial metadata structs".into()]),
         metadata: Default::default(),
         kind: Kind::Struct {
-            name: "OptionVec",
+            name: "OptionVec".into(),
             children: vec![
-                Entry { label: "label", docs: Some(vec!["Name used"]), metadata: Default::default(), has_default: false, type_info: Descriptor { docs: None, metadata: Default::default(), kind: Kind::Option(Box::new(String::metadata())) }, aliases: &["label"] },
-                Entry { label: "score", docs: None, metadata: [("active", "true")].into_iter().collect(), has_default: false, type_info: Descriptor { docs: None, metadata: Default::default(), kind: Kind::Option(Box::new(u64::metadata())) }, aliases: &["score"] },
-                Entry { label: "attached", docs: None, metadata: [("active", "false")].into_iter().collect(), has_default: false, type_info: Descriptor { docs: None, metadata: Default::default(), kind: Kind::Sequence(Box::new(u64::metadata())) }, aliases: &["attached"] },
+                Entry::new("label", Descriptor { docs: None, metadata: Default::default(), kind: Kind::Option(Box::new(String::metadata())) }).with_docs(Some(vec!["Name used".into()])).with_aliases(["label"]),
+                Entry::new("score", Descriptor { docs: None, metadata: Default::default(), kind: Kind::Option(Box::new(u64::metadata())) }).with_metadata([("active", "true")].into_iter().collect()).with_aliases(["score"]),
+                Entry::new("attached", Descriptor { docs: None, metadata: Default::default(), kind: Kind::Sequence(Box::new(u64::metadata())) }).with_metadata([("active", "false")].into_iter().collect()).with_aliases(["attached"]),
             ]
         }
     });
@@ -2,7 +2,7 @@
 
 use pretty_assertions::assert_eq;
 
-use struct_metadata::{Kind, Described, Descriptor, Entry, MetadataKind};
+use struct_metadata::{Kind, Described, MetadataKind};
 
 
 #[derive(Default, PartialEq, Eq, Debug, MetadataKind, Clone)]
@@ -29,7 +29,7 @@ struct NoneFeatured;
 #[test]
 fn single_featured() {
     let data = SingleFeatured::metadata();
-    assert_eq!(data.kind, Kind::Struct{ name: "SingleFeatured", children: vec![]});
+    assert_eq!(data.kind, Kind::Struct{ name: "SingleFeatured".into(), children: vec![]});
     assert_eq!(data.docs, None);
     assert_eq!(data.metadata, Properties{important: true, cats: ""});
 }
@@ -37,7 +37,7 @@ fn single_featured() {
 #[test]
 fn dual_featured() {
     let data = DoubleFeatured::metadata();
-    assert_eq!(data.kind, Kind::Struct{ name: "DoubleFeatured", children: vec![]});
+    assert_eq!(data.kind, Kind::Struct{ name: "DoubleFeatured".into(), children: vec![]});
     assert_eq!(data.docs, None);
     assert_eq!(data.metadata, Properties{important: true, cats: "Less than 10"});
 }
@@ -45,7 +45,7 @@ fn dual_featured() {
 #[test]
 fn none_featured() {
     let data = NoneFeatured::metadata();
-    assert_eq!(data.kind, Kind::Struct{ name: "NoneFeatured", children: vec![]});
+    assert_eq!(data.kind, Kind::Struct{ name: "NoneFeatured".into(), children: vec![]});
     assert_eq!(data.docs, None);
     assert_eq!(data.metadata, Properties{..Default::default()});
 }
@@ -70,7 +70,7 @@ struct DoubleVecFeatured;
 #[test]
 fn single_vec_featured() {
     let data = SingleVecFeatured::metadata();
-    assert_eq!(data.kind, Kind::Struct{ name: "SingleVecFeatured", children: vec![]});
+    assert_eq!(data.kind, Kind::Struct{ name: "SingleVecFeatured".into(), children: vec![]});
     assert_eq!(data.docs, None);
     assert_eq!(data.metadata, vec![("important", "true")]);
 }
@@ -78,7 +78,7 @@ fn single_vec_featured() {
 #[test]
 fn dual_vec_featured() {
     let data = DoubleVecFeatured::metadata();
-    assert_eq!(data.kind, Kind::Struct{ name: "DoubleVecFeatured", children: vec![]});
+    assert_eq!(data.kind, Kind::Struct{ name: "DoubleVecFeatured".into(), children: vec![]});
     assert_eq!(data.docs, None);
     assert_eq!(data.metadata, vec![("important", "true"), ("cats", "\"Less than 10\"")]);
 }
@@ -102,24 +102,12 @@ struct Fields {
 }
 
 
-fn expected_fields_metadata() -> Descriptor<Properties> {
-    Descriptor {
-        docs: Some(vec!["non trivial metadata structs"]),
-        metadata: Properties { important: true, cats: "" },
-        kind: Kind::Struct {
-            name: "Fields",
-            children: vec![
-                Entry { label: "label", docs: Some(vec!["Name used"]), has_default: false, metadata: Default::default(), type_info: u64::metadata(), aliases: &["label"] },
-                Entry { label: "description", docs: None, has_default: false, metadata: Properties { cats: "fluffy", ..Default::default() }, type_info: String::metadata(), aliases: &["description"] },
-                Entry { label: "cats", docs: Some(vec!["Are cats allowed here?"]), has_default: false, metadata: Properties { important: true, cats: "" }, type_info: bool::metadata(), aliases: &["cats"] },
-            ]
-        }
-    }
-}
+/// Directory holding the committed snapshots
+const SCHEMAS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/schemas");
 
 #[test]
 fn fields() {
-    assert_eq!(Fields::metadata(), expected_fields_metadata());
+    struct_metadata::assert_snapshot!(SCHEMAS, Fields::metadata());
 }
 
 
@@ -136,19 +124,18 @@ struct Nested {
     data: Fields
 }
 
+impl struct_metadata::snapshot::SnapshotMetadata for Properties {
+    fn snapshot(&self) -> serde_json::Value {
+        if *self == Properties::default() {
+            return serde_json::Value::Null
+        }
+        serde_json::json!({"important": self.important, "cats": self.cats})
+    }
+}
+
 #[test]
 fn nested() {
-    assert_eq!(Nested::metadata(), Descriptor{
-        docs: Some(vec!["nested structs"]),
-        metadata: Properties { important: true, cats: "" },
-        kind: Kind::Struct {
-            name: "Nested",
-            children: vec![
-                Entry { label: "label", docs: Some(vec!["Name used"]), has_default: false, metadata: Default::default(), type_info: u64::metadata(), aliases: &["label"] },
-                Entry { label: "data", docs: None, has_default: false, metadata: Properties { cats: "with stripes", ..Default::default() }, type_info: expected_fields_metadata(), aliases: &["data"] },
-            ]
-        }
-    });
+    struct_metadata::assert_snapshot!(SCHEMAS, Nested::metadata());
 }
 
 
@@ -170,7 +157,7 @@ fn nested() {
 // #[test]
 // fn newtype() {
 
-//     let newtype_kind = Kind::Aliased { name: "Newtype", kind: Box::new(u64::metadata()) };
+//     let newtype_kind = Kind::Aliased { name: "Newtype".into(), kind: Box::new(u64::metadata()) };
 
 //     let newtype = Descriptor {
 //         docs: None,
@@ -188,10 +175,10 @@ fn nested() {
 //         docs: None,
 //         metadata: Default::default(),
 //         kind: Kind::Struct {
-//             name: "UseNewtype",
+//             name: "UseNewtype".into(),
 //             children: vec![
-//                 Entry { label: "data", docs: None, has_default: false, metadata: Properties { important: true, ..Default::default() }, type_info: newtype, aliases: &["data"] },
-//                 Entry { label: "odata", docs: None, has_default: false, metadata: Properties { important: true, ..Default::default() }, type_info: newtype_option, aliases: &["odata"] },
+//                 Entry::new("data", newtype).with_metadata(Properties { important: true, ..Default::default() }).with_aliases(["data"]),
+//                 Entry::new("odata", newtype_option).with_metadata(Properties { important: true, ..Default::default() }).with_aliases(["odata"]),
 //             ]
 //         }
 //     });
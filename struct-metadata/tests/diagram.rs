@@ -0,0 +1,105 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::Described;
+use struct_metadata::diagram::Diagram;
+
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Config {
+    bind: String,
+    log: Option<Logging>,
+    hosts: Vec<Host>,
+    routes: HashMap<String, Route>,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Logging {
+    level: Level,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum Level {
+    Error,
+    Info,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Host(String);
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Route {
+    target: Host,
+}
+
+#[test]
+fn mermaid_class() {
+    assert_eq!(Diagram::new().mermaid_class([&Config::metadata()]), r#"classDiagram
+    class Config {
+        +String bind
+        +Option~Logging~ log
+        +Vec~Host~ hosts
+        +HashMap~String, Route~ routes
+    }
+    class Host {
+        <<alias>>
+        String
+    }
+    class Level {
+        <<enumeration>>
+        Error
+        Info
+    }
+    class Logging {
+        +Level level
+    }
+    class Route {
+        +Host target
+    }
+    Config --> Logging : log (Option)
+    Config --> "*" Host : hosts (Sequence)
+    Config --> "*" Route : routes (Mapping value)
+    Logging --> Level : level
+    Route --> Host : target
+"#);
+}
+
+#[test]
+fn mermaid_er() {
+    assert_eq!(Diagram::new().collapse_leaves(true).mermaid_er([&Config::metadata()]), r#"erDiagram
+    Config {
+        String bind
+        Option[Logging] log
+        Vec[Host] hosts
+        HashMap[String_Route] routes
+    }
+    Logging {
+        Level level
+    }
+    Route {
+        Host target
+    }
+    Config ||--o| Logging : "log (Option)"
+    Config ||--o{ Route : "routes (Mapping value)"
+"#);
+}
+
+#[test]
+fn dot() {
+    assert_eq!(Diagram::new().max_depth(1).collapse_leaves(true).dot([&Config::metadata()]), r#"digraph types {
+    node [shape=record];
+    "Config" [label="{Config|bind: String\llog: Option\<Logging\>\lhosts: Vec\<Host\>\lroutes: HashMap\<String, Route\>\l}"];
+    "Logging" [label="{Logging|level: Level\l}"];
+    "Route" [label="{Route|target: Host\l}"];
+    "Config" -> "Logging" [label="log (Option)"];
+    "Config" -> "Route" [label="routes (Mapping value)"];
+}
+"#);
+}
@@ -0,0 +1,183 @@
+#![cfg(test)]
+
+use pretty_assertions::assert_eq;
+use struct_metadata::{ChangeKind, Compatibility, Described};
+
+mod v1 {
+    use struct_metadata::Described;
+
+    #[derive(Described, serde::Deserialize)]
+    #[allow(dead_code)]
+    pub struct Record {
+        pub id: u32,
+        pub name: String,
+        pub note: Option<String>,
+        pub legacy: u8,
+        pub status: Status,
+        pub tags: Vec<String>,
+    }
+
+    #[derive(Described, serde::Deserialize)]
+    #[allow(dead_code)]
+    pub enum Status {
+        Active,
+        Retired,
+    }
+}
+
+mod v2 {
+    use struct_metadata::Described;
+
+    #[derive(Described, serde::Deserialize)]
+    #[allow(dead_code)]
+    pub struct Record {
+        pub id: u64,
+        #[serde(alias = "name")]
+        pub title: String,
+        #[metadata(index: true)]
+        pub note: String,
+        pub status: Status,
+        pub tags: Vec<u8>,
+        #[serde(default)]
+        pub created: u64,
+    }
+
+    #[derive(Described, serde::Deserialize)]
+    #[allow(dead_code)]
+    pub enum Status {
+        Active,
+        Retired,
+        Pending,
+    }
+}
+
+#[test]
+fn identical() {
+    let diff = v1::Record::metadata().diff(&v1::Record::metadata());
+    assert!(diff.is_empty());
+    assert_eq!(diff.compatibility(), Compatibility::Full);
+}
+
+#[test]
+fn changes() {
+    let diff = v1::Record::metadata().diff(&v2::Record::metadata());
+    let changes: Vec<_> = diff.changes.iter().map(|change| (change.path.as_str(), change.kind.clone(), change.compatibility)).collect();
+    assert_eq!(changes, vec![
+        ("id", ChangeKind::TypeChanged { from: "u32".to_owned(), to: "u64".to_owned() }, Compatibility::Backward),
+        ("title", ChangeKind::FieldRenamed { from: "name".to_owned(), to: "title".to_owned() }, Compatibility::Backward),
+        ("note", ChangeKind::MetadataChanged { from: "{}".to_owned(), to: "{\"index\": \"true\"}".to_owned() }, Compatibility::Full),
+        ("note", ChangeKind::BecameRequired, Compatibility::Forward),
+        ("legacy", ChangeKind::FieldRemoved { optional: false }, Compatibility::Backward),
+        ("status", ChangeKind::VariantAdded { label: "Pending".to_owned() }, Compatibility::Backward),
+        ("tags.[]", ChangeKind::TypeChanged { from: "String".to_owned(), to: "u8".to_owned() }, Compatibility::None),
+        ("created", ChangeKind::FieldAdded { optional: true }, Compatibility::Full),
+    ]);
+    assert_eq!(diff.compatibility(), Compatibility::None);
+
+    assert_eq!(diff.to_string(), [
+        "compatibility: none",
+        "  - id: changed type from u32 to u64 (backward)",
+        "  - title: renamed field from name to title (backward)",
+        "  - note: changed metadata from {} to {\"index\": \"true\"} (full)",
+        "  - note: became required (forward)",
+        "  - legacy: removed required field (backward)",
+        "  - status: added variant Pending (backward)",
+        "  - tags.[]: changed type from String to u8 (none)",
+        "  - created: added optional field (full)",
+        "",
+    ].join("\n"));
+}
+
+#[test]
+fn reversed() {
+    let diff = v2::Record::metadata().diff(&v1::Record::metadata());
+    let kinds: Vec<_> = diff.changes.iter().map(|change| (change.path.as_str(), change.compatibility)).collect();
+    assert_eq!(kinds, vec![
+        ("id", Compatibility::Forward),
+        ("name", Compatibility::Forward),
+        ("note", Compatibility::Full),
+        ("note", Compatibility::Backward),
+        ("status", Compatibility::Forward),
+        ("tags.[]", Compatibility::None),
+        ("created", Compatibility::Full),
+        ("legacy", Compatibility::Forward),
+    ]);
+}
+
+mod merged {
+    use struct_metadata::Described;
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub struct Before {
+        pub x: u32,
+        pub y: u32,
+    }
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub struct After {
+        #[serde(alias = "x")]
+        pub y: u32,
+    }
+}
+
+#[test]
+fn labels_match_before_aliases() {
+    let diff = merged::Before::metadata().diff(&merged::After::metadata());
+    let changes: Vec<_> = diff.changes.iter().map(|change| (change.path.as_str(), change.kind.clone())).collect();
+    assert_eq!(changes, vec![
+        ("x", ChangeKind::FieldRemoved { optional: false }),
+    ]);
+}
+
+mod owned_v1 {
+    use struct_metadata::Described;
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub struct Record {
+        #[serde(default)]
+        pub limit: Option<u32>,
+        pub inner: Inner,
+    }
+
+    #[derive(Described)]
+    #[metadata(team: "search", owner: "ana")]
+    #[allow(dead_code)]
+    pub struct Inner {
+        pub value: u32,
+    }
+}
+
+mod owned_v2 {
+    use struct_metadata::Described;
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub struct Record {
+        #[serde(default)]
+        pub limit: u32,
+        pub inner: Inner,
+    }
+
+    #[derive(Described)]
+    #[metadata(team: "search", owner: "bo")]
+    #[allow(dead_code)]
+    pub struct Inner {
+        pub value: u32,
+    }
+}
+
+#[test]
+fn nested_metadata() {
+    let diff = owned_v1::Record::metadata().diff(&owned_v2::Record::metadata());
+    let changes: Vec<_> = diff.changes.iter().map(|change| (change.path.as_str(), change.kind.clone())).collect();
+    assert_eq!(changes, vec![
+        ("inner", ChangeKind::MetadataChanged {
+            from: r#"{"owner": "\"ana\"", "team": "\"search\""}"#.to_owned(),
+            to: r#"{"owner": "\"bo\"", "team": "\"search\""}"#.to_owned(),
+        }),
+    ]);
+    assert_eq!(diff.compatibility(), Compatibility::Full);
+}
@@ -0,0 +1,77 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::{Described, Kind};
+
+type Descriptor = struct_metadata::Descriptor<HashMap<&'static str, &'static str>>;
+
+
+/// A user account
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+struct Account {
+    /// Login name
+    #[serde(alias = "login")]
+    name: String,
+    #[metadata(index: true, analyzed: false)]
+    emails: Option<Vec<String>>,
+    #[serde(default)]
+    groups: HashMap<String, Group>,
+    role: Role,
+}
+
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+struct Group {
+    id: u64,
+}
+
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+enum Role {
+    /// Full access
+    Admin,
+    #[serde(alias = "guest")]
+    User,
+}
+
+#[test]
+fn type_expressions() {
+    let data: Descriptor = Option::<Vec<String>>::metadata();
+    assert_eq!(data.kind.type_expression(), "Option<Vec<String>>");
+    let data: Descriptor = HashMap::<String, Group>::metadata();
+    assert_eq!(data.kind.type_expression(), "HashMap<String, Group>");
+    assert_eq!(Kind::<HashMap<&'static str, &'static str>>::JSON.type_expression(), "Value");
+}
+
+#[test]
+fn render_tree() {
+    assert_eq!(Account::metadata().to_string(), [
+        "/// A user account",
+        "Account",
+        "    /// Login name",
+        "    name: String (aliases: name, login)",
+        "    emails: Option<Vec<String>> {\"analyzed\": \"false\", \"index\": \"true\"}",
+        "    groups: HashMap<String, Group> = default",
+        "        id: u64",
+        "    role: Role",
+        "        /// Full access",
+        "        Admin",
+        "        User (aliases: User, guest)",
+        "",
+    ].join("\n"));
+}
+
+#[test]
+fn render_options() {
+    assert_eq!(Account::metadata().pretty().hide_docs().hide_metadata().max_depth(1).to_string(), [
+        "Account",
+        "    name: String (aliases: name, login)",
+        "    emails: Option<Vec<String>>",
+        "    groups: HashMap<String, Group> = default",
+        "    role: Role",
+        "",
+    ].join("\n"));
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "elasticsearch")]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use struct_metadata::Described;
+use struct_metadata::elasticsearch::{compare_mappings, Elastic, MappingDifference};
+
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Report {
+    #[metadata(copy_to: "all_text")]
+    id: String,
+    #[metadata(analyzer: "standard", store: true)]
+    summary: Option<String>,
+    #[metadata(index: false)]
+    size: u64,
+    created: chrono::DateTime<chrono::Utc>,
+    // leaf parameters are left off object and nested fields
+    #[metadata(index: false, copy_to: "all_text")]
+    results: Vec<Result>,
+    #[metadata(mapping: "object", store: true)]
+    parts: Vec<Result>,
+    labels: HashMap<String, Label>,
+    extra: serde_json::Value,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Result {
+    score: f32,
+    verdict: Verdict,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Label {
+    #[metadata(index: false)]
+    value: i32,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum Verdict {
+    Safe,
+    Malicious,
+}
+
+fn expected() -> serde_json::Value {
+    json!({
+        "mappings": {
+            "dynamic": "strict",
+            "properties": {
+                "id": {"type": "keyword", "copy_to": ["all_text"]},
+                "summary": {"type": "text", "analyzer": "standard", "store": true},
+                "size": {"type": "unsigned_long", "index": false},
+                "created": {"type": "date"},
+                "results": {"type": "nested", "properties": {
+                    "score": {"type": "float"},
+                    "verdict": {"type": "keyword"},
+                }},
+                "parts": {"type": "object", "properties": {
+                    "score": {"type": "float"},
+                    "verdict": {"type": "keyword"},
+                }},
+                "labels": {"type": "object", "dynamic": true},
+                "extra": {"type": "flattened"},
+            },
+            "dynamic_templates": [
+                {"labels.*.value": {"path_match": "labels.*.value", "mapping": {"type": "integer", "index": false}}},
+            ],
+        }
+    })
+}
+
+#[test]
+fn index_body() {
+    assert_eq!(Elastic::new().index_body(&Report::metadata()), expected());
+}
+
+#[test]
+fn compare() {
+    let generated = Elastic::new().index_body(&Report::metadata());
+    assert!(compare_mappings(&generated, &expected()).is_empty());
+
+    let mut existing = expected();
+    existing["mappings"]["properties"]["size"]["index"] = json!(true);
+    existing["mappings"]["properties"]["results"]["properties"].as_object_mut().unwrap().remove("score");
+    existing["mappings"]["properties"]["old"] = json!({"type": "keyword"});
+
+    let differences = compare_mappings(&generated, &existing);
+    assert_eq!(differences, vec![
+        MappingDifference::Missing { path: "results.score".to_owned() },
+        MappingDifference::Changed { path: "size".to_owned(), parameter: "index".to_owned(), expected: json!(false), actual: json!(true) },
+        MappingDifference::Unexpected { path: "old".to_owned() },
+    ]);
+    assert_eq!(differences[1].to_string(), "size: index expected false found true");
+}
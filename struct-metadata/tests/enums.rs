@@ -17,12 +17,12 @@ fn enum_display() {
         docs: None,
         metadata: Default::default(),
         kind: struct_metadata::Kind::Enum {
-            name: "ExtendedScanValues",
+            name: "ExtendedScanValues".into(),
             variants: vec![
-                Variant{ label: "submitted", docs: None, metadata: Default::default(), aliases: &["submitted"] },
-                Variant{ label: "skipped", docs: None, metadata: Default::default(), aliases: &["skipped"] },
-                Variant{ label: "incomplete", docs: None, metadata: Default::default(), aliases: &["incomplete"] },
-                Variant{ label: "complete", docs: None, metadata: [("ideal", "true")].into_iter().collect(), aliases: &["complete"] },
+                Variant{ label: "submitted".into(), docs: None, metadata: Default::default(), aliases: vec!["submitted".into()] },
+                Variant{ label: "skipped".into(), docs: None, metadata: Default::default(), aliases: vec!["skipped".into()] },
+                Variant{ label: "incomplete".into(), docs: None, metadata: Default::default(), aliases: vec!["incomplete".into()] },
+                Variant{ label: "complete".into(), docs: None, metadata: [("ideal", "true")].into_iter().collect(), aliases: vec!["complete".into()] },
             ]
         }
     })
@@ -0,0 +1,58 @@
+#![cfg(test)]
+
+use struct_metadata::{Described, FingerprintOptions};
+
+
+mod documented {
+    /// Documented record
+    #[derive(struct_metadata::Described)]
+    #[allow(dead_code)]
+    pub struct Record {
+        /// Identifier
+        pub id: u64,
+        #[metadata(index: true)]
+        pub tags: Vec<String>,
+    }
+}
+
+mod plain {
+    #[derive(struct_metadata::Described)]
+    #[allow(dead_code)]
+    pub struct Record {
+        pub id: u64,
+        pub tags: Vec<String>,
+        #[metadata(volatile: true)]
+        pub cached: Option<u32>,
+    }
+}
+
+mod changed {
+    #[derive(struct_metadata::Described)]
+    #[allow(dead_code)]
+    pub struct Record {
+        pub id: u32,
+        pub tags: Vec<String>,
+    }
+}
+
+#[test]
+fn stable() {
+    assert_eq!(format!("{:016x}", documented::Record::metadata().fingerprint()), "5774aa718a34bee4");
+}
+
+#[test]
+fn structure_only() {
+    let documented = documented::Record::metadata().fingerprint();
+    assert_eq!(documented, plain::Record::metadata().fingerprint());
+    assert_ne!(documented, changed::Record::metadata().fingerprint());
+}
+
+#[test]
+fn options() {
+    let with_metadata = FingerprintOptions { metadata: true, skip_volatile: true };
+    assert_ne!(documented::Record::metadata().fingerprint_with(with_metadata), documented::Record::metadata().fingerprint());
+    assert_ne!(documented::Record::metadata().fingerprint_with(with_metadata), changed::Record::metadata().fingerprint_with(with_metadata));
+
+    let keep_volatile = FingerprintOptions { metadata: false, skip_volatile: false };
+    assert_ne!(plain::Record::metadata().fingerprint_with(keep_volatile), documented::Record::metadata().fingerprint_with(keep_volatile));
+}
@@ -0,0 +1,56 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use struct_metadata::{Described, Kind, MappingPolicy};
+
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Leaf {
+    name: String,
+    #[metadata(index: false)]
+    count: u32,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Record {
+    id: u64,
+    #[metadata(index: true, store: true)]
+    leaf: Leaf,
+    leaves: Vec<Option<Leaf>>,
+    labels: HashMap<String, Leaf>,
+    maybe: Option<bool>,
+}
+
+#[test]
+fn wildcard_mappings() {
+    let data = Record::metadata();
+    let fields = data.flatten(MappingPolicy::Wildcard);
+    let summary: Vec<_> = fields.iter().map(|field| (field.path.as_str(), field.kind.name(), field.multivalued, field.optional)).collect();
+    assert_eq!(summary, vec![
+        ("id", "u64", false, false),
+        ("leaf.name", "string", false, false),
+        ("leaf.count", "u32", false, false),
+        ("leaves.name", "string", true, true),
+        ("leaves.count", "u32", true, true),
+        ("labels.*.name", "string", false, false),
+        ("labels.*.count", "u32", false, false),
+        ("maybe", "bool", false, true),
+    ]);
+
+    assert_eq!(fields[0].metadata, HashMap::new());
+    assert_eq!(fields[1].metadata, [("index", "true"), ("store", "true")].into_iter().collect());
+    assert_eq!(fields[2].metadata, [("index", "false"), ("store", "true")].into_iter().collect());
+    assert_eq!(fields[4].metadata, [("index", "false")].into_iter().collect());
+}
+
+#[test]
+fn stop_at_mappings() {
+    let data = Record::metadata();
+    let fields = data.flatten(MappingPolicy::Stop);
+    let paths: Vec<_> = fields.iter().map(|field| field.path.as_str()).collect();
+    assert_eq!(paths, vec!["id", "leaf.name", "leaf.count", "leaves.name", "leaves.count", "labels", "maybe"]);
+    assert!(matches!(fields[5].kind, Kind::Mapping(..)));
+}
@@ -0,0 +1,124 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::Described;
+use struct_metadata::graphql::GraphQL;
+
+
+/// A scanned file
+///
+/// Stored once per hash
+#[derive(Described, serde::Serialize)]
+#[allow(dead_code)]
+struct File {
+    /// Content hash
+    sha256: String,
+    size: u64,
+    #[serde(rename = "mime-type")]
+    mime_type: Option<String>,
+    tags: Vec<Option<Tag>>,
+    scores: HashMap<String, f32>,
+    status: Status,
+    #[metadata(deprecated: "use status")]
+    done: Option<bool>,
+    #[serde(skip_serializing)]
+    secret: String,
+}
+
+#[derive(Described, serde::Serialize)]
+#[allow(dead_code)]
+struct Tag {
+    name: String,
+    #[metadata(deprecated: true)]
+    weight: i32,
+}
+
+#[derive(Described, serde::Serialize)]
+#[allow(dead_code)]
+enum Status {
+    #[serde(rename = "new")]
+    New,
+    InProgress,
+    #[metadata(deprecated: true)]
+    Failed,
+}
+
+#[test]
+fn objects() {
+    assert_eq!(GraphQL::new().generate([&File::metadata()]), r#"scalar JSON
+
+"""
+A scanned file
+
+Stored once per hash
+"""
+type File {
+  "Content hash"
+  sha256: String!
+  size: String!
+  mime_type: String
+  tags: [Tag]!
+  scores: JSON!
+  status: Status!
+  done: Boolean @deprecated(reason: "use status")
+}
+
+enum Status {
+  NEW
+  IN_PROGRESS
+  FAILED @deprecated
+}
+
+type Tag {
+  name: String!
+  weight: Int! @deprecated
+}
+"#);
+}
+
+#[test]
+fn inputs() {
+    let schema = GraphQL::new().inputs(true).json_scalar("Map").big_integer_scalar("BigInt").generate([&File::metadata()]);
+    assert!(schema.starts_with("scalar BigInt\nscalar Map\n"));
+    assert!(schema.contains("input TagInput {\n  name: String!\n  weight: Int!\n}\n"));
+    assert!(schema.contains("  tags: [TagInput]!\n"));
+    assert!(schema.contains("input FileInput {\n"));
+    assert!(schema.contains("  secret: String!\n}\n\nenum Status {"));
+}
+
+#[derive(Described, serde::Serialize)]
+#[allow(dead_code)]
+struct Job {
+    owner: Option<Owner>,
+    #[metadata(deprecated: "use owner")]
+    owners: Vec<Owner>,
+    level: Level,
+}
+
+#[derive(Described, serde::Serialize)]
+#[metadata(deprecated: "use Job.owner")]
+#[allow(dead_code)]
+struct Owner {
+    name: String,
+}
+
+#[derive(Described, serde::Serialize)]
+#[metadata(deprecated: true)]
+#[allow(dead_code)]
+enum Level {
+    Low,
+}
+
+#[test]
+fn deprecated_types() {
+    let schema = GraphQL::new().generate([&Job::metadata()]);
+    assert!(schema.contains(r#"type Job {
+  owner: Owner @deprecated(reason: "use Job.owner")
+  owners: [Owner!]! @deprecated(reason: "use owner")
+  level: Level! @deprecated
+}
+"#), "{schema}");
+    assert!(schema.contains("type Owner {\n"));
+}
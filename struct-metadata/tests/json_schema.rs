@@ -0,0 +1,81 @@
+#![cfg(feature = "json_schema")]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use struct_metadata::Described;
+use struct_metadata::json_schema::JsonSchema;
+
+
+/// A stored document
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+struct Document {
+    /// Unique id
+    id: u32,
+    #[serde(alias = "name")]
+    title: String,
+    summary: Option<String>,
+    #[serde(default)]
+    #[metadata(max_length: 16)]
+    tags: Vec<String>,
+    state: State,
+    scores: HashMap<State, Score>,
+}
+
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+struct Score(f64);
+
+/// Processing state
+#[derive(Described, serde::Deserialize, PartialEq, Eq, Hash)]
+#[allow(dead_code)]
+enum State {
+    New,
+    #[serde(alias = "done")]
+    Finished,
+}
+
+#[test]
+fn document() {
+    assert_eq!(Document::metadata().json_schema(), json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Document",
+        "description": "A stored document",
+        "type": "object",
+        "properties": {
+            "id": {"type": "integer", "minimum": 0, "maximum": 4294967295u32, "description": "Unique id"},
+            "title": {"type": "string"},
+            "name": {"type": "string"},
+            "summary": {"type": ["string", "null"]},
+            "tags": {"type": "array", "items": {"type": "string"}},
+            "state": {"$ref": "#/$defs/State"},
+            "scores": {
+                "type": "object",
+                "additionalProperties": {"$ref": "#/$defs/Score"},
+                "propertyNames": {"$ref": "#/$defs/State"},
+            },
+        },
+        "required": ["id", "state", "scores"],
+        "anyOf": [{"required": ["title"]}, {"required": ["name"]}],
+        "$defs": {
+            "State": {"type": "string", "enum": ["New", "Finished", "done"], "description": "Processing state"},
+            "Score": {"type": "number"},
+        },
+    }));
+}
+
+#[test]
+fn hook() {
+    let generator = JsonSchema::new()
+        .reference_prefix("#/components/schemas/")
+        .with_hook(|metadata: &HashMap<&'static str, &'static str>, schema| {
+            if let Some(length) = metadata.get("max_length") {
+                schema.insert("maxItems".to_owned(), json!(length.parse::<u64>().unwrap()));
+            }
+        });
+    let schema = generator.generate(&Document::metadata());
+    assert_eq!(schema["properties"]["tags"], json!({"type": "array", "items": {"type": "string"}, "maxItems": 16}));
+    assert_eq!(schema["properties"]["state"], json!({"$ref": "#/components/schemas/State"}));
+}
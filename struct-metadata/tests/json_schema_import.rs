@@ -0,0 +1,145 @@
+#![cfg(feature = "json_schema")]
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use struct_metadata::json_schema::{import, ImportIssue};
+use struct_metadata::{Descriptor, Described, Entry, Kind, Variant};
+
+type Metadata = HashMap<String, String>;
+
+fn leaf(kind: Kind<Metadata>) -> Descriptor<Metadata> {
+    Descriptor { docs: None, metadata: Default::default(), kind }
+}
+
+#[test]
+fn import_schema() {
+    let schema = json!({
+        "title": "Pet",
+        "description": "An animal in the store",
+        "type": "object",
+        "required": ["name", "kind"],
+        "properties": {
+            "name": {"type": "string", "description": "Name of the pet", "minLength": 1},
+            "kind": {"$ref": "#/$defs/Kind"},
+            "born": {"type": ["string", "null"], "format": "date-time"},
+            "age": {"type": "integer", "minimum": 0, "maximum": 255, "readOnly": true},
+            "tags": {"type": "array", "items": {"type": "string"}},
+            "extra": {"type": "object", "additionalProperties": {"type": "number"}},
+        },
+        "$defs": {
+            "Kind": {"enum": ["cat", "dog"], "description": "Species"},
+        }
+    });
+
+    let imported = import::<Metadata>(&schema);
+    assert_eq!(imported.issues, vec![]);
+    assert_eq!(imported.descriptor, Descriptor {
+        docs: Some(vec!["An animal in the store".into()]),
+        metadata: Default::default(),
+        kind: Kind::Struct { name: "Pet".into(), children: vec![
+            Entry::new("age", leaf(Kind::U8)).with_default(true).with_aliases(["age"]).with_skip_deserializing(true),
+            Entry::new("born", leaf(Kind::Option(Box::new(leaf(Kind::DateTime))))).with_aliases(["born"]),
+            Entry::new("extra", leaf(Kind::Mapping(Box::new(leaf(Kind::String)), Box::new(leaf(Kind::F64))))).with_default(true).with_aliases(["extra"]),
+            Entry::new("kind", Descriptor { docs: Some(vec!["Species".into()]), metadata: Default::default(), kind: Kind::Enum { name: "Kind".into(), variants: vec![
+                Variant { label: "cat".into(), docs: None, metadata: Default::default(), aliases: vec!["cat".into()] },
+                Variant { label: "dog".into(), docs: None, metadata: Default::default(), aliases: vec!["dog".into()] },
+            ]}}).with_aliases(["kind"]),
+            Entry::new("name", leaf(Kind::String)).with_docs(Some(vec!["Name of the pet".into()])).with_metadata([("minLength".to_owned(), "1".to_owned())].into_iter().collect()).with_aliases(["name"]),
+            Entry::new("tags", leaf(Kind::Sequence(Box::new(leaf(Kind::String))))).with_default(true).with_aliases(["tags"]),
+        ]}
+    });
+}
+
+#[test]
+fn unmapped_keywords() {
+    let schema = json!({"type": "string", "pattern": "^[a-z]+$", "format": "email"});
+    let imported = import::<Metadata>(&schema);
+    assert_eq!(imported.descriptor.kind, Kind::String);
+    assert_eq!(imported.descriptor.metadata, [("pattern", "\"^[a-z]+$\""), ("format", "\"email\"")].map(|(key, value)| (key.to_owned(), value.to_owned())).into_iter().collect());
+}
+
+/// A stored record
+#[derive(Described)]
+#[allow(dead_code)]
+struct Record {
+    id: u32,
+    summary: Option<String>,
+    state: State,
+    code: Code,
+    counts: HashMap<String, i16>,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum State {
+    Open,
+    Closed,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Code(String);
+
+#[test]
+fn exported_schemas_import() {
+    let imported = import::<Metadata>(&Record::metadata().json_schema());
+    assert_eq!(imported.issues, vec![]);
+    let Kind::Struct { name, children } = &imported.descriptor.kind else { panic!() };
+    assert_eq!(*name, "Record");
+    let kinds: Vec<_> = children.iter().map(|child| (child.label.as_ref(), child.type_info.kind.type_expression(), child.has_default)).collect();
+    assert_eq!(kinds, vec![
+        ("code", "Code".to_owned(), false),
+        ("counts", "HashMap<String, i16>".to_owned(), false),
+        ("id", "u32".to_owned(), false),
+        ("state", "State".to_owned(), false),
+        ("summary", "Option<String>".to_owned(), false),
+    ]);
+    assert!(matches!(&children[0].type_info.kind, Kind::Aliased { kind, .. } if kind.kind == Kind::String));
+}
+
+#[test]
+fn issues() {
+    let schema = json!({
+        "type": "object",
+        "required": ["missing"],
+        "properties": {
+            "either": {"anyOf": [{"type": "string"}, {"type": "integer"}]},
+            "int": {"type": "int"},
+            "nothing": {"type": "null"},
+            "remote": {"$ref": "https://example.com/schema.json"},
+            "a/b": {"enum": [1, 2]},
+            "tree": {"$ref": "#/$defs/Tree"},
+        },
+        "$defs": {
+            "Tree": {"type": "object", "properties": {"children": {"type": "array", "items": {"$ref": "#/$defs/Tree"}}}},
+        }
+    });
+    let imported = import::<Metadata>(&schema);
+    let issues: Vec<String> = imported.issues.iter().map(ImportIssue::to_string).collect();
+    assert_eq!(issues, vec![
+        "/required: required property missing isn't defined",
+        "/properties/a~1b/enum: only enums of strings can be described",
+        "/properties/either/anyOf: alternatives other than null can't be described",
+        "/properties/int/type: unknown type int",
+        "/properties/nothing/type: a null value on its own can't be described",
+        "/properties/remote/$ref: only references within the document are supported, found https://example.com/schema.json",
+        "/$defs/Tree/properties/children/items/$ref: recursive reference to #/$defs/Tree can't be described",
+    ]);
+    let Kind::Struct { children, .. } = &imported.descriptor.kind else { panic!() };
+    assert_eq!(children[1].type_info.kind, Kind::JSON);
+}
+
+#[test]
+fn strings_are_owned() {
+    let descriptor = {
+        let schema = json!({"title": "Owned", "type": "object", "properties": {"field": {"type": "string"}}});
+        import::<Metadata>(&schema).descriptor
+    };
+    let Kind::Struct { name, children } = descriptor.kind else { panic!() };
+    assert!(matches!(name, Cow::Owned(_)));
+    assert_eq!(name, "Owned");
+    assert!(matches!(&children[0].label, Cow::Owned(label) if label == "field"));
+}
@@ -0,0 +1,199 @@
+#![cfg(feature = "kubernetes")]
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use struct_metadata::kubernetes::{Crd, CrdError};
+use struct_metadata::Described;
+
+
+/// Desired state of a widget
+#[derive(Described)]
+#[metadata(group: "example.com", version: "v1alpha1", short_names: "wd", printer_columns: "Replicas=.spec.replicas, Ready=.status.ready, Age=.metadata.creationTimestamp")]
+#[allow(dead_code)]
+struct WidgetSpec {
+    /// Number of copies to run
+    replicas: u32,
+    #[serde(default)]
+    image: Option<String>,
+    #[metadata(list_type: "map", list_map_keys: "name")]
+    ports: Vec<Port>,
+    #[serde(default)]
+    config: serde_json::Value,
+    policy: Policy,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Port {
+    name: String,
+    number: u16,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum Policy {
+    Always,
+    Never,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum Format {
+    #[serde(rename = ".inf")]
+    Infinite,
+    #[serde(rename = ".NaN")]
+    Missing,
+    #[serde(rename = "0x1F")]
+    Hex,
+    #[serde(rename = "0o17")]
+    Octal,
+    #[serde(rename = "2024-01-01")]
+    Date,
+    #[serde(rename = "On")]
+    Enabled,
+    #[serde(rename = ".hidden")]
+    Hidden,
+    #[serde(rename = "v1.2")]
+    Versioned,
+}
+
+#[derive(Described)]
+#[metadata(group: "example.com", version: "v1")]
+#[allow(dead_code)]
+struct FormatSpec {
+    format: Format,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct WidgetStatus {
+    ready: bool,
+}
+
+#[test]
+fn schema() {
+    let spec = WidgetSpec::metadata();
+    let status = WidgetStatus::metadata();
+    let schema = Crd::new(&spec).status(&status).schema().unwrap();
+    assert_eq!(schema, json!({
+        "type": "object",
+        "required": ["spec"],
+        "properties": {
+            "spec": {
+                "type": "object",
+                "description": "Desired state of a widget",
+                "required": ["replicas", "ports", "policy"],
+                "properties": {
+                    "replicas": {"type": "integer", "format": "int64", "minimum": 0, "description": "Number of copies to run"},
+                    "image": {"type": "string", "nullable": true},
+                    "ports": {
+                        "type": "array",
+                        "x-kubernetes-list-type": "map",
+                        "x-kubernetes-list-map-keys": ["name"],
+                        "items": {
+                            "type": "object",
+                            "required": ["name", "number"],
+                            "properties": {
+                                "name": {"type": "string"},
+                                "number": {"type": "integer", "format": "int64", "minimum": 0},
+                            }
+                        }
+                    },
+                    "config": {"x-kubernetes-preserve-unknown-fields": true},
+                    "policy": {"type": "string", "enum": ["Always", "Never"]},
+                }
+            },
+            "status": {
+                "type": "object",
+                "required": ["ready"],
+                "properties": {"ready": {"type": "boolean"}},
+            }
+        }
+    }));
+    assert!(!schema.to_string().contains("$ref"));
+}
+
+#[test]
+fn definition() {
+    let spec = WidgetSpec::metadata();
+    let status = WidgetStatus::metadata();
+    let yaml = Crd::new(&spec).status(&status).yaml().unwrap();
+    let prefix = r#"apiVersion: apiextensions.k8s.io/v1
+kind: CustomResourceDefinition
+metadata:
+  name: widgets.example.com
+spec:
+  group: example.com
+  names:
+    kind: Widget
+    listKind: WidgetList
+    plural: widgets
+    shortNames:
+      - wd
+    singular: widget
+  scope: Namespaced
+  versions:
+    - additionalPrinterColumns:
+        - jsonPath: .spec.replicas
+          name: Replicas
+          type: integer
+        - jsonPath: .status.ready
+          name: Ready
+          type: boolean
+        - jsonPath: .metadata.creationTimestamp
+          name: Age
+          type: date
+      name: v1alpha1
+      schema:
+        openAPIV3Schema:
+          properties:
+            spec:
+              description: Desired state of a widget
+              properties:
+                config:
+                  x-kubernetes-preserve-unknown-fields: true
+                image:
+                  nullable: true
+                  type: string
+"#;
+    assert_eq!(&yaml[..prefix.len()], prefix);
+    assert!(yaml.ends_with("      served: true\n      storage: true\n      subresources:\n        status: {}\n"), "{yaml}");
+}
+
+#[derive(Described)]
+#[metadata(group: "example.com", version: "v1")]
+#[allow(dead_code)]
+struct BadSpec {
+    #[metadata(list_type: "map", list_map_keys: "missing")]
+    ports: Vec<Port>,
+}
+
+#[test]
+fn errors() {
+    let spec = BadSpec::metadata();
+    assert_eq!(Crd::new(&spec).schema().unwrap_err(), CrdError::InvalidListType {
+        path: "spec.ports".to_owned(),
+        reason: "list map key missing isn't a field of the items".to_owned(),
+    });
+
+    assert_eq!(Crd::new(&WidgetStatus::metadata()).definition().unwrap_err(), CrdError::MissingSetting { setting: "group" });
+
+    let spec = WidgetSpec::metadata();
+    assert_eq!(Crd::new(&spec).definition().unwrap_err(), CrdError::UnknownColumnPath { path: ".status.ready".to_owned() });
+}
+
+#[test]
+fn quoted_scalars() {
+    let yaml = Crd::new(&FormatSpec::metadata()).yaml().unwrap();
+    let values = r#"                  enum:
+                    - ".inf"
+                    - ".NaN"
+                    - "0x1F"
+                    - "0o17"
+                    - "2024-01-01"
+                    - "On"
+                    - .hidden
+                    - v1.2
+"#;
+    assert!(yaml.contains(values), "{yaml}");
+}
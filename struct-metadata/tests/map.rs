@@ -0,0 +1,73 @@
+#![cfg(test)]
+
+use struct_metadata::{Described, Kind};
+
+
+#[derive(Default, PartialEq, Eq, Debug)]
+struct Index {
+    path: String,
+    index: bool,
+}
+
+/// Outer type
+#[derive(Described)]
+#[allow(dead_code)]
+#[metadata(index: true)]
+struct Outer {
+    #[metadata(index: true)]
+    inner: Option<Inner>,
+    #[metadata(index: false)]
+    color: Color,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Inner {
+    #[metadata(index: true)]
+    value: u64,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum Color {
+    #[metadata(index: true)]
+    Red,
+    Blue,
+}
+
+#[test]
+fn map_to_struct() {
+    let data = Outer::metadata().map_metadata(|path, metadata| Index {
+        path: path.join("."),
+        index: metadata.get("index") == Some(&"true"),
+    });
+
+    assert_eq!(data.docs, Some(vec!["Outer type".into()]));
+    assert_eq!(data.metadata, Index { path: "".to_owned(), index: true });
+    let Kind::Struct { name, children } = data.kind else { panic!() };
+    assert_eq!(name, "Outer");
+    assert_eq!(children[0].metadata, Index { path: "inner".to_owned(), index: true });
+    let Kind::Option(inner) = &children[0].type_info.kind else { panic!() };
+    let Kind::Struct { children: inner_children, .. } = &inner.kind else { panic!() };
+    assert_eq!(inner_children[0].metadata, Index { path: "inner.value".to_owned(), index: true });
+
+    assert_eq!(children[1].metadata, Index { path: "color".to_owned(), index: false });
+    let Kind::Enum { variants, .. } = &children[1].type_info.kind else { panic!() };
+    assert_eq!(variants[0].metadata, Index { path: "color.Red".to_owned(), index: true });
+    assert_eq!(variants[1].metadata, Index { path: "color.Blue".to_owned(), index: false });
+}
+
+#[test]
+fn try_map_errors() {
+    let result = Outer::metadata().try_map_metadata(|path, metadata| {
+        match metadata.get("index") {
+            Some(&"true") | None => Ok(()),
+            Some(other) => Err(format!("{}: {other}", path.join("."))),
+        }
+    });
+    assert_eq!(result.unwrap_err(), "color: false");
+
+    let result = Inner::metadata().try_map_metadata(|_, metadata| -> Result<_, String> { Ok(metadata.len()) });
+    let Kind::Struct { children, .. } = result.unwrap().kind else { panic!() };
+    assert_eq!(children[0].metadata, 1);
+}
@@ -0,0 +1,89 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::Described;
+use struct_metadata::markdown::Markdown;
+
+
+/// Service configuration
+///
+/// Loaded at startup
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    /// Address to listen on, as host|port
+    #[metadata(env: "BIND")]
+    bind: String,
+    #[serde(default, alias = "worker_count")]
+    workers: u32,
+    /// Log settings
+    log: Option<Logging>,
+    hosts: Vec<Host>,
+    labels: HashMap<String, String>,
+}
+
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+struct Logging {
+    level: Level,
+}
+
+/// Verbosity of logging
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+enum Level {
+    /// Only errors
+    Error,
+    #[serde(alias = "warning")]
+    Warn,
+    Info,
+}
+
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+struct Host(String);
+
+#[test]
+fn reference() {
+    let markdown = Markdown::new()
+        .title("Configuration")
+        .column("Environment", |metadata: &HashMap<&'static str, &'static str>| metadata.get("env").map(|value| format!("`{}`", value.trim_matches('"'))));
+    assert_eq!(markdown.generate([&Config::metadata()]), r#"# Configuration
+
+## Config
+
+Service configuration
+
+Loaded at startup
+
+| Field | Type | Required | Aliases | Description | Environment |
+| --- | --- | --- | --- | --- | --- |
+| `bind` | String | required |  | Address to listen on, as host\|port | `BIND` |
+| `workers` | u32 | default | `worker_count` |  |  |
+| `log` | Option&lt;[Logging](#logging)&gt; | optional |  | Log settings |  |
+| `hosts` | Vec&lt;[Host](#host)&gt; | required |  |  |  |
+| `labels` | HashMap&lt;String, String&gt; | required |  |  |  |
+
+## Host
+
+Alias of String.
+
+## Level
+
+Verbosity of logging
+
+Variants:
+
+- `Error`: Only errors
+- `Warn` (aliases: `warning`)
+- `Info`
+
+## Logging
+
+| Field | Type | Required | Aliases | Description | Environment |
+| --- | --- | --- | --- | --- | --- |
+| `level` | [Level](#level) | required |  |  |  |
+"#);
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "openapi")]
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use struct_metadata::openapi::{Method, OpenApi, Operation};
+
+
+/// A new user to create
+#[derive(struct_metadata::Described, serde::Serialize, serde::Deserialize)]
+#[allow(dead_code)]
+struct CreateUser {
+    name: String,
+    #[serde(skip_serializing)]
+    password: String,
+    #[serde(skip_deserializing)]
+    id: u64,
+    nickname: Option<String>,
+}
+
+/// The stored user
+#[derive(struct_metadata::Described, serde::Serialize, serde::Deserialize)]
+#[allow(dead_code)]
+struct User {
+    id: u64,
+    role: Role,
+}
+
+#[derive(struct_metadata::Described, serde::Serialize, serde::Deserialize)]
+#[allow(dead_code)]
+enum Role {
+    Admin,
+    Member,
+}
+
+#[test]
+fn document() {
+    let document = OpenApi::new("Users", "1.0.0")
+        .register::<Role>()
+        .operation(Operation::new(Method::Post, "/users")
+            .operation_id("create_user")
+            .request::<CreateUser>()
+            .response::<User>(201))
+        .operation(Operation::new(Method::Delete, "/users/{id}").empty_response(204))
+        .build();
+
+    assert_eq!(document, json!({
+        "openapi": "3.1.0",
+        "info": {"title": "Users", "version": "1.0.0"},
+        "paths": {
+            "/users": {
+                "post": {
+                    "operationId": "create_user",
+                    "requestBody": {
+                        "required": true,
+                        "description": "A new user to create",
+                        "content": {"application/json": {"schema": {"$ref": "#/components/schemas/CreateUser"}}},
+                    },
+                    "responses": {
+                        "201": {
+                            "description": "The stored user",
+                            "content": {"application/json": {"schema": {"$ref": "#/components/schemas/User"}}},
+                        },
+                    },
+                },
+            },
+            "/users/{id}": {
+                "delete": {"responses": {"204": {"description": "No content"}}},
+            },
+        },
+        "components": {
+            "schemas": {
+                "Role": {"type": "string", "enum": ["Admin", "Member"]},
+                "CreateUser": {
+                    "type": "object",
+                    "description": "A new user to create",
+                    "properties": {
+                        "name": {"type": "string"},
+                        "password": {"type": "string", "writeOnly": true},
+                        "id": {"type": "integer", "minimum": 0, "maximum": u64::MAX, "readOnly": true},
+                        "nickname": {"type": ["string", "null"]},
+                    },
+                    "required": ["name", "password"],
+                },
+                "User": {
+                    "type": "object",
+                    "description": "The stored user",
+                    "properties": {
+                        "id": {"type": "integer", "minimum": 0, "maximum": u64::MAX},
+                        "role": {"$ref": "#/components/schemas/Role"},
+                    },
+                    "required": ["id", "role"],
+                },
+            },
+        },
+    }));
+}
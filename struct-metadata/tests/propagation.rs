@@ -182,7 +182,7 @@ fn flattened_metadata() {
     let Kind::Struct {children, ..} = InlineOuterMetadata::metadata().kind else { panic!() };
     assert_eq!(children.len(), 3);
     for child in children {
-        match child.label {
+        match child.label.as_ref() {
             "other" => assert_eq!(child.metadata, Meta { index: Some(false) }),
             "other2" => assert_eq!(child.metadata, Meta { index: None }),
             "a" => assert_eq!(child.metadata, Meta { index: Some(true) }),
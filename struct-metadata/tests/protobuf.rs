@@ -0,0 +1,105 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::Described;
+use struct_metadata::protobuf::{Protobuf, ProtobufError, ProtobufMetadata};
+
+
+/// A scanned file
+#[derive(Described)]
+#[allow(dead_code)]
+struct File {
+    /// Content hash
+    sha256: String,
+    #[metadata(field_number: 2)]
+    size: u64,
+    #[serde(rename = "mime-type")]
+    mime_type: Option<String>,
+    #[metadata(field_number: 1)]
+    tags: Vec<Tag>,
+    scores: HashMap<String, f32>,
+    status: Status,
+    seen: chrono::DateTime<chrono::Utc>,
+    #[serde(skip)]
+    cache: u32,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Tag(String);
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum Status {
+    #[serde(rename = "new")]
+    New,
+    #[serde(rename = "in-progress")]
+    InProgress,
+}
+
+#[test]
+fn messages() {
+    assert_eq!(Protobuf::new().package("scanner.v1").generate([&File::metadata()]).unwrap(), r#"syntax = "proto3";
+
+package scanner.v1;
+
+import "google/protobuf/timestamp.proto";
+
+// A scanned file
+message File {
+    // Content hash
+    string sha256 = 3;
+    uint64 size = 2;
+    optional string mime_type = 4 [json_name = "mime-type"];
+    repeated string tags = 1;
+    map<string, float> scores = 5;
+    Status status = 6;
+    google.protobuf.Timestamp seen = 7;
+}
+
+enum Status {
+    STATUS_UNSPECIFIED = 0;
+    STATUS_NEW = 1;
+    STATUS_IN_PROGRESS = 2;
+}
+"#);
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Nested {
+    grid: Vec<Vec<u32>>,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Clash {
+    #[metadata(field_number: 1)]
+    first: u32,
+    #[metadata(field_number: 1)]
+    second: u32,
+}
+
+#[test]
+fn errors() {
+    assert_eq!(Protobuf::new().generate([&Nested::metadata()]), Err(ProtobufError::Unsupported {
+        path: "Nested.grid".to_owned(),
+        reason: "repeated fields can't contain lists or maps",
+    }));
+    assert_eq!(Protobuf::new().generate([&Clash::metadata()]), Err(ProtobufError::DuplicateNumber {
+        path: "Clash.second".to_owned(),
+        number: 1,
+    }));
+}
+
+#[test]
+fn field_number_metadata() {
+    let number = |value: &'static str| HashMap::from([("field_number", value)]).field_number();
+    assert_eq!(number("3"), Some(3));
+    assert_eq!(number("\"3\""), Some(3));
+    // only the pair of quotes left by the derive macro is removed
+    assert_eq!(number("\"\"3\""), None);
+    assert_eq!(number("3\""), None);
+}
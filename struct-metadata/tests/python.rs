@@ -0,0 +1,117 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::Described;
+use struct_metadata::python::{EnumStyle, ModelStyle, Python};
+
+
+/// A scanned file
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+struct File {
+    /// Content hash
+    sha256: String,
+    #[serde(rename = "mime-type")]
+    mime_type: Option<String>,
+    #[serde(default, alias = "labels")]
+    tags: Vec<Tag>,
+    #[serde(default)]
+    source: Source,
+    scores: HashMap<String, f32>,
+    status: Status,
+    #[serde(skip)]
+    cache: u32,
+}
+
+#[derive(Described, serde::Deserialize, Default)]
+#[allow(dead_code)]
+struct Source {
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+struct Tag(String);
+
+/// Processing state
+#[derive(Described, serde::Deserialize)]
+#[allow(dead_code)]
+enum Status {
+    #[serde(rename = "new")]
+    New,
+    #[serde(rename = "in-progress")]
+    InProgress,
+}
+
+#[test]
+fn pydantic() {
+    assert_eq!(Python::new().generate([&File::metadata()]), r#"from __future__ import annotations
+
+from enum import Enum
+from typing import Optional
+
+from pydantic import AliasChoices, BaseModel, Field
+
+
+class Source(BaseModel):
+    name: str = Field(default="")
+
+
+class Status(str, Enum):
+    """Processing state"""
+    NEW = "new"
+    IN_PROGRESS = "in-progress"
+
+
+Tag = str
+
+
+class File(BaseModel):
+    """A scanned file"""
+
+    sha256: str = Field(description="Content hash")
+    mime_type: Optional[str] = Field(default=None, alias="mime-type")
+    tags: list[Tag] = Field(default_factory=list, validation_alias=AliasChoices("tags", "labels"))
+    source: Source = Field(default_factory=Source)
+    scores: dict[str, float]
+    status: Status
+"#);
+}
+
+#[test]
+fn dataclass() {
+    let python = Python::new().model_style(ModelStyle::Dataclass).enum_style(EnumStyle::Literal);
+    assert_eq!(python.generate([&File::metadata()]), r#"from __future__ import annotations
+
+from dataclasses import dataclass, field
+from typing import Literal, Optional
+
+
+@dataclass(kw_only=True)
+class Source:
+    name: str = field(default="")
+
+
+Status = Literal["new", "in-progress"]
+"""Processing state"""
+
+
+Tag = str
+
+
+@dataclass(kw_only=True)
+class File:
+    """A scanned file"""
+
+    # Content hash
+    sha256: str
+    mime_type: Optional[str] = field(default=None)
+    tags: list[Tag] = field(default_factory=list)
+    source: Source = field(default_factory=Source)
+    scores: dict[str, float]
+    status: Status
+"#);
+}
@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::rust::Rust;
+use struct_metadata::{assert_descriptor_eq, Described};
+
+
+/// Settings for a job
+///
+/// Loaded from the job file
+#[derive(Described)]
+#[metadata(table: "jobs")]
+#[allow(dead_code)]
+struct Job {
+    /// Name of the job
+    #[metadata(index: true)]
+    #[serde(alias = "title")]
+    name: String,
+    #[serde(rename = "retryCount", default)]
+    retry_count: u32,
+    #[serde(skip)]
+    cache: Option<String>,
+    tags: Vec<Tag>,
+    labels: HashMap<String, Priority>,
+    #[serde(rename = "type")]
+    job_type: Identifier,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Tag {
+    value: String,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Identifier(u64);
+
+/// How soon a job runs
+#[derive(Described)]
+#[allow(dead_code)]
+enum Priority {
+    /// Run first
+    #[metadata(weight: 10)]
+    High,
+    #[serde(rename = "low", alias = "Lowest")]
+    Low,
+}
+
+mod generated {
+    #![allow(dead_code)]
+    include!("rust/generated.rs");
+}
+
+#[test]
+fn generate() {
+    let output = Rust::new().generate([&Job::metadata()]);
+    assert_eq!(output, include_str!("rust/generated.rs"));
+}
+
+#[test]
+fn round_trip() {
+    // aliases are left out of descriptor equality but compared here
+    assert_descriptor_eq!(Job::metadata(), generated::Job::metadata());
+    assert_descriptor_eq!(Priority::metadata(), generated::Priority::metadata());
+}
@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+use struct_metadata::Described;
+
+#[derive(Debug, Clone, Described)]
+pub struct Identifier(pub u64);
+
+/// Settings for a job
+///
+/// Loaded from the job file
+#[derive(Debug, Clone, Described)]
+#[metadata(table: "jobs")]
+pub struct Job {
+    /// Name of the job
+    #[metadata(index: true)]
+    #[serde(alias = "title")]
+    pub name: String,
+    #[serde(rename = "retryCount", default)]
+    pub retry_count: u32,
+    #[serde(skip)]
+    pub cache: Option<String>,
+    pub tags: Vec<Tag>,
+    pub labels: HashMap<String, Priority>,
+    #[serde(rename = "type")]
+    pub type_: Identifier,
+}
+
+/// How soon a job runs
+#[derive(Debug, Clone, Described)]
+pub enum Priority {
+    /// Run first
+    #[metadata(weight: 10)]
+    High,
+    #[serde(rename = "low", alias = "Lowest")]
+    Low,
+}
+
+#[derive(Debug, Clone, Described)]
+pub struct Tag {
+    pub value: String,
+}
@@ -18,9 +18,9 @@ fn rename_field() {
         docs: None,
         metadata: Default::default(),
         kind: Kind::Struct {
-            name: "RenameFieldTestType",
+            name: "RenameFieldTestType".into(),
             children: vec![
-                Entry { label: "type", docs: None, has_default: false, metadata: Default::default(), type_info: String::metadata(), aliases: &["type"] }
+                Entry::new("type", String::metadata()).with_aliases(["type"])
             ]
         }
     });
@@ -39,9 +39,9 @@ fn rename_variant() {
         docs: None,
         metadata: Default::default(),
         kind: struct_metadata::Kind::Enum {
-            name: "RenameVarient",
+            name: "RenameVarient".into(),
             variants: vec![
-                Variant{ label: "type", docs: None, metadata: Default::default(), aliases: &["type", "kind"] },
+                Variant{ label: "type".into(), docs: None, metadata: Default::default(), aliases: vec!["type".into(), "kind".into()] },
             ]
         }
     })
@@ -61,9 +61,9 @@ fn rename_all_varients() {
         docs: None,
         metadata: Default::default(),
         kind: struct_metadata::Kind::Enum {
-            name: "OuterName",
+            name: "OuterName".into(),
             variants: vec![
-                Variant{ label: "TYPE", docs: None, metadata: Default::default(), aliases: &["TYPE"] },
+                Variant{ label: "TYPE".into(), docs: None, metadata: Default::default(), aliases: vec!["TYPE".into()] },
             ]
         }
     })
@@ -81,15 +81,40 @@ fn rename_all_fields() {
         docs: None,
         metadata: Default::default(),
         kind: Kind::Struct {
-            name: "RenameAllField",
+            name: "RenameAllField".into(),
             children: vec![
-                Entry { label: "inner", docs: None, has_default: false, metadata: Default::default(), type_info: u8::metadata(), aliases: &["inner"] }
+                Entry::new("inner", u8::metadata()).with_aliases(["inner"])
             ]
         }
     });
 }
 
 
+#[derive(Serialize, Deserialize, Described)]
+#[allow(dead_code)]
+struct SkipFields {
+    #[serde(skip)]
+    hidden: u8,
+    #[serde(skip_serializing)]
+    password: String,
+    #[serde(skip_deserializing)]
+    created: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+#[test]
+fn skip_fields() {
+    let Kind::Struct { children, .. } = SkipFields::metadata().kind else { panic!() };
+    let flags: Vec<_> = children.iter().map(|child| (child.label.as_ref(), child.skip_serializing, child.skip_deserializing)).collect();
+    assert_eq!(flags, vec![
+        ("hidden", true, true),
+        ("password", true, false),
+        ("created", false, true),
+        ("note", false, false),
+    ]);
+}
+
 // #[test]
 // fn rename_struct() {
 //     panic!();
@@ -12,7 +12,7 @@ struct EmptyB {}
 #[test]
 fn empty_a() {
     let data = EmptyA::metadata();
-    assert_eq!(data.kind, Kind::Struct{ name: "EmptyA", children: vec![]});
+    assert_eq!(data.kind, Kind::Struct{ name: "EmptyA".into(), children: vec![]});
     assert_eq!(data.docs, None);
     assert!(data.metadata.is_empty());
 }
@@ -20,7 +20,7 @@ fn empty_a() {
 #[test]
 fn empty_b() {
     let data = EmptyB::metadata();
-    assert_eq!(data.kind, Kind::Struct{ name: "EmptyB", children: vec![]});
+    assert_eq!(data.kind, Kind::Struct{ name: "EmptyB".into(), children: vec![]});
     assert_eq!(data.docs, None);
     assert!(data.metadata.is_empty());
 }
@@ -39,16 +39,16 @@ struct EmptyDocB {}
 #[test]
 fn empty_doc_a() {
     let data = EmptyDocA::metadata();
-    assert_eq!(data.kind, Kind::Struct{name: "EmptyDocA", children: vec![]});
-    assert_eq!(data.docs, Some(vec!["Docstring"]));
+    assert_eq!(data.kind, Kind::Struct{name: "EmptyDocA".into(), children: vec![]});
+    assert_eq!(data.docs, Some(vec!["Docstring".into()]));
     assert!(data.metadata.is_empty());
 }
 
 #[test]
 fn empty_doc_b() {
     let data = EmptyDocB::metadata();
-    assert_eq!(data.kind, Kind::Struct{name: "EmptyDocB", children: vec![]});
-    assert_eq!(data.docs, Some(vec!["The", "", "Docstring"]));
+    assert_eq!(data.kind, Kind::Struct{name: "EmptyDocB".into(), children: vec![]});
+    assert_eq!(data.docs, Some(vec!["The".into(), "".into(), "Docstring".into()]));
     assert!(data.metadata.is_empty());
 }
 
@@ -60,7 +60,7 @@ struct Single(u64);
 #[test]
 fn single() {
     let data = Single::metadata();
-    assert_eq!(data.kind, Kind::Aliased { name: "Single", kind: Box::new(u64::metadata()) });
+    assert_eq!(data.kind, Kind::Aliased { name: "Single".into(), kind: Box::new(u64::metadata()) });
     assert_eq!(data.docs, None);
     assert!(data.metadata.is_empty());
 }
@@ -76,7 +76,7 @@ struct DoubleFeatured;
 #[test]
 fn single_featured() {
     let data = SingleFeatured::metadata();
-    assert_eq!(data.kind, Kind::Struct{ name: "SingleFeatured", children: vec![]});
+    assert_eq!(data.kind, Kind::Struct{ name: "SingleFeatured".into(), children: vec![]});
     assert_eq!(data.docs, None);
     assert_eq!(data.metadata, [("important", "true")].into_iter().collect());
 }
@@ -84,7 +84,7 @@ fn single_featured() {
 #[test]
 fn dual_featured() {
     let data = DoubleFeatured::metadata();
-    assert_eq!(data.kind, Kind::Struct{ name: "DoubleFeatured", children: vec![]});
+    assert_eq!(data.kind, Kind::Struct{ name: "DoubleFeatured".into(), children: vec![]});
     assert_eq!(data.docs, None);
     assert_eq!(data.metadata, [("important", "true"), ("cats", "\"Less than 10\"")].into_iter().collect());
 }
@@ -109,9 +109,9 @@ fn simple_fields() {
     let data = SimpleFields::metadata();
     assert_eq!(data.docs, None);
     assert_eq!(data.metadata, [("important", "true")].into_iter().collect());
-    assert_eq!(data.kind, Kind::Struct{ name: "SimpleFields", children: vec![
-        Entry { label: "label", docs: Some(vec!["Name used"]), has_default: false, metadata: Default::default(), type_info: u64::metadata(), aliases: &["label"] },
-        Entry { label: "description", docs: None, has_default: false, metadata: [("text", "true")].into_iter().collect(), type_info: String::metadata(), aliases: &["description"] },
-        Entry { label: "cats", docs: Some(vec!["Are cats allowed here?"]), has_default: false, metadata: [("important", "true")].into_iter().collect(), type_info: bool::metadata(), aliases: &["cats"] },
+    assert_eq!(data.kind, Kind::Struct{ name: "SimpleFields".into(), children: vec![
+        Entry::new("label", u64::metadata()).with_docs(Some(vec!["Name used".into()])).with_aliases(["label"]),
+        Entry::new("description", String::metadata()).with_metadata([("text", "true")].into_iter().collect()).with_aliases(["description"]),
+        Entry::new("cats", bool::metadata()).with_docs(Some(vec!["Are cats allowed here?".into()])).with_metadata([("important", "true")].into_iter().collect()).with_aliases(["cats"]),
     ]});
 }
@@ -0,0 +1,104 @@
+#![cfg(feature = "snapshot")]
+
+use std::path::PathBuf;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::snapshot::{differences, snapshot, SnapshotError, Snapshots};
+use struct_metadata::Described;
+
+
+/// A stored user
+#[derive(Described)]
+#[metadata(table: "users")]
+#[allow(dead_code)]
+struct User {
+    /// Login name
+    #[serde(alias = "login")]
+    name: String,
+    #[serde(default)]
+    age: Option<u8>,
+    role: Role,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum Role {
+    Admin,
+    Guest,
+}
+
+mod changed {
+    use struct_metadata::Described;
+
+    /// A stored user
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub struct User {
+        /// Login name
+        pub name: String,
+        pub age: Option<u16>,
+        pub role: Role,
+        pub email: String,
+    }
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub enum Role {
+        Admin,
+        Member,
+    }
+}
+
+/// Empty directory for a test to write snapshots into
+fn directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("struct-metadata-snapshot-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    directory
+}
+
+#[test]
+fn canonical_form() {
+    assert_eq!(snapshot(&User::metadata()), serde_json::json!({
+        "kind": "struct",
+        "name": "User",
+        "docs": ["A stored user"],
+        "metadata": {"table": "\"users\""},
+        "fields": [
+            {"label": "name", "docs": ["Login name"], "aliases": ["login"], "type": {"kind": "String"}},
+            {"label": "age", "default": true, "type": {"kind": "option", "type": {"kind": "u8"}}},
+            {"label": "role", "type": {"kind": "enum", "name": "Role", "variants": [{"label": "Admin"}, {"label": "Guest"}]}},
+        ]
+    }));
+}
+
+#[test]
+fn write_and_check() {
+    let directory = directory("check");
+    let snapshots = Snapshots::new(&directory).update(false);
+    assert_eq!(snapshots.check(&User::metadata()), Err(SnapshotError::Missing { path: directory.join("User.json") }));
+
+    Snapshots::new(&directory).update(true).check(&User::metadata()).unwrap();
+    assert!(std::fs::read_to_string(directory.join("User.json")).unwrap().ends_with("}\n"));
+    snapshots.check(&User::metadata()).unwrap();
+
+    let Err(error) = snapshots.check(&changed::User::metadata()) else { panic!("change not detected") };
+    let SnapshotError::Changed { differences, .. } = &error else { panic!("{error}") };
+    assert_eq!(differences, &vec![
+        "fields.name.aliases: removed (was [\"login\"])".to_owned(),
+        "fields.age.default: removed (was true)".to_owned(),
+        "fields.age.type.type.kind: \"u8\" -> \"u16\"".to_owned(),
+        "fields.role.type.variants.Guest: removed".to_owned(),
+        "fields.role.type.variants.Member: added".to_owned(),
+        "fields.email: added".to_owned(),
+        "metadata: removed (was {\"table\":\"\\\"users\\\"\"})".to_owned(),
+    ]);
+    assert!(error.to_string().starts_with(&format!("schema snapshot {} is out of date, run with UPDATE_SCHEMAS=1 to update it\n    fields.name.aliases: removed", directory.join("User.json").display())));
+    let _ = std::fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn field_order() {
+    let expected = serde_json::json!({"fields": [{"label": "a"}, {"label": "b"}]});
+    let actual = serde_json::json!({"fields": [{"label": "b"}, {"label": "a"}]});
+    assert_eq!(differences(&expected, &actual), vec!["fields: order changed from [a, b] to [b, a]"]);
+}
@@ -0,0 +1,151 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::Described;
+use struct_metadata::sql::{Dialect, Sql, SqlError};
+
+
+/// A scanned file
+#[derive(Described)]
+#[allow(dead_code)]
+struct File {
+    /// Content hash
+    #[metadata(primary_key: true)]
+    sha256: String,
+    #[metadata(index: true)]
+    size: u64,
+    source: Option<Source>,
+    #[metadata(storage: "json")]
+    origin: Source,
+    status: Status,
+    #[metadata(storage: "table")]
+    tags: Vec<String>,
+    scores: HashMap<String, f32>,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Source {
+    name: String,
+    #[metadata(unique: true)]
+    url: String,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum Status {
+    New,
+    Done,
+}
+
+#[test]
+fn sqlite() {
+    assert_eq!(Sql::new(Dialect::Sqlite).create_tables(&File::metadata()).unwrap(), r#"-- A scanned file
+CREATE TABLE "file" (
+    -- Content hash
+    "sha256" TEXT NOT NULL,
+    "size" INTEGER NOT NULL,
+    "source_name" TEXT,
+    "source_url" TEXT,
+    "origin" TEXT NOT NULL,
+    "status" TEXT NOT NULL CONSTRAINT "file_status_check" CHECK ("status" IN ('New', 'Done')),
+    "scores" TEXT NOT NULL,
+    PRIMARY KEY ("sha256")
+);
+
+CREATE INDEX "file_size_idx" ON "file" ("size");
+
+CREATE UNIQUE INDEX "file_source_url_key" ON "file" ("source_url");
+
+CREATE TABLE "file_tags" (
+    "file_sha256" TEXT NOT NULL,
+    "position" INTEGER NOT NULL,
+    "value" TEXT NOT NULL,
+    PRIMARY KEY ("file_sha256", "position"),
+    FOREIGN KEY ("file_sha256") REFERENCES "file" ("sha256") ON DELETE CASCADE
+);
+"#);
+}
+
+#[test]
+fn postgres() {
+    assert_eq!(Sql::new(Dialect::Postgres).create_tables(&File::metadata()).unwrap(), r#"CREATE TABLE "file" (
+    "sha256" TEXT NOT NULL,
+    "size" NUMERIC(20) NOT NULL,
+    "source_name" TEXT,
+    "source_url" TEXT,
+    "origin" JSONB NOT NULL,
+    "status" TEXT NOT NULL CONSTRAINT "file_status_check" CHECK ("status" IN ('New', 'Done')),
+    "scores" JSONB NOT NULL,
+    PRIMARY KEY ("sha256")
+);
+
+COMMENT ON TABLE "file" IS 'A scanned file';
+
+COMMENT ON COLUMN "file"."sha256" IS 'Content hash';
+
+CREATE INDEX "file_size_idx" ON "file" ("size");
+
+CREATE UNIQUE INDEX "file_source_url_key" ON "file" ("source_url");
+
+CREATE TABLE "file_tags" (
+    "file_sha256" TEXT NOT NULL,
+    "position" BIGINT NOT NULL,
+    "value" TEXT NOT NULL,
+    PRIMARY KEY ("file_sha256", "position"),
+    FOREIGN KEY ("file_sha256") REFERENCES "file" ("sha256") ON DELETE CASCADE
+);
+"#);
+}
+
+#[test]
+fn mysql() {
+    let tables = Sql::new(Dialect::MySql).tables(&File::metadata()).unwrap();
+    assert_eq!(tables[0].column("sha256").unwrap().sql_type, "VARCHAR(255)");
+    assert_eq!(tables[0].column("source_url").unwrap().sql_type, "VARCHAR(255)");
+    assert_eq!(tables[0].column("source_name").unwrap().sql_type, "TEXT");
+    assert_eq!(Sql::new(Dialect::MySql).create_table(&tables[0])[0].lines().next_back(), Some(") COMMENT='A scanned file';"));
+    assert_eq!(Sql::new(Dialect::MySql).column_definition("file", tables[0].column("sha256").unwrap()), "`sha256` VARCHAR(255) NOT NULL COMMENT 'Content hash'");
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Unkeyed {
+    #[metadata(storage: "table")]
+    tags: Vec<String>,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct StructKeyed {
+    #[metadata(primary_key: true)]
+    source: Source,
+    #[metadata(storage: "table")]
+    tags: Vec<String>,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct JsonKeyed {
+    #[metadata(primary_key: true, storage: "json")]
+    source: Source,
+}
+
+#[test]
+fn errors() {
+    assert_eq!(Sql::new(Dialect::Sqlite).tables(&Unkeyed::metadata()), Err(SqlError::MissingPrimaryKey {
+        table: "unkeyed".to_owned(),
+        column: "tags".to_owned(),
+    }));
+    assert_eq!(Sql::new(Dialect::Sqlite).tables(&StructKeyed::metadata()), Err(SqlError::Unsupported {
+        column: "source".to_owned(),
+        reason: "primary keys can only be scalar columns",
+    }));
+    assert_eq!(Sql::new(Dialect::Sqlite).tables(&JsonKeyed::metadata()), Err(SqlError::Unsupported {
+        column: "source".to_owned(),
+        reason: "primary keys can only be scalar columns",
+    }));
+    assert_eq!(Sql::new(Dialect::Sqlite).tables(&Status::metadata()), Err(SqlError::NotAStruct { name: "Status".to_owned() }));
+}
@@ -0,0 +1,138 @@
+#![cfg(test)]
+
+use pretty_assertions::assert_eq;
+use struct_metadata::Described;
+use struct_metadata::sql::{Dialect, Sql};
+
+
+mod old {
+    use struct_metadata::Described;
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub struct File {
+        #[metadata(primary_key: true)]
+        pub sha256: String,
+        pub size: u32,
+        pub name: String,
+        pub label: Option<String>,
+        pub status: Status,
+    }
+
+    #[derive(Described)]
+    #[allow(dead_code)]
+    pub enum Status {
+        New,
+        Done,
+        Failed,
+    }
+}
+
+mod new {
+    use struct_metadata::Described;
+
+    #[derive(Described, serde::Deserialize)]
+    #[allow(dead_code)]
+    pub struct File {
+        #[metadata(primary_key: true)]
+        pub sha256: String,
+        pub size: u64,
+        #[serde(alias = "name")]
+        pub filename: String,
+        #[metadata(index: true)]
+        pub source: Option<String>,
+        pub status: Status,
+    }
+
+    #[derive(Described, serde::Deserialize)]
+    #[allow(dead_code)]
+    pub enum Status {
+        New,
+        Done,
+    }
+}
+
+#[test]
+fn postgres() {
+    let migration = Sql::new(Dialect::Postgres).migration(&old::File::metadata(), &new::File::metadata()).unwrap();
+    assert!(migration.is_destructive());
+    assert_eq!(migration.to_string(), r#"-- rename column file.name to filename
+ALTER TABLE "file" RENAME COLUMN "name" TO "filename";
+
+-- add column file.source
+ALTER TABLE "file" ADD COLUMN "source" TEXT;
+
+-- DESTRUCTIVE: change type of file.size from BIGINT to NUMERIC(20)
+ALTER TABLE "file" ALTER COLUMN "size" TYPE NUMERIC(20) USING "size"::NUMERIC(20);
+
+-- drop allowed values of file.status
+ALTER TABLE "file" DROP CONSTRAINT "file_status_check";
+
+-- DESTRUCTIVE: limit file.status to New, Done
+ALTER TABLE "file" ADD CONSTRAINT "file_status_check" CHECK ("status" IN ('New', 'Done'));
+
+-- DESTRUCTIVE: drop column file.label
+ALTER TABLE "file" DROP COLUMN "label";
+
+-- create index file_source_idx
+CREATE INDEX "file_source_idx" ON "file" ("source");
+"#);
+}
+
+#[test]
+fn sqlite_rebuild() {
+    let migration = Sql::new(Dialect::Sqlite).migration(&old::File::metadata(), &new::File::metadata()).unwrap();
+    assert_eq!(migration.to_string(), r#"-- DESTRUCTIVE: rebuild table file, drops column label, limits the values of status
+PRAGMA foreign_keys = OFF;
+BEGIN TRANSACTION;
+CREATE TABLE "file_new" (
+    "sha256" TEXT NOT NULL,
+    "size" INTEGER NOT NULL,
+    "filename" TEXT NOT NULL,
+    "source" TEXT,
+    "status" TEXT NOT NULL CONSTRAINT "file_status_check" CHECK ("status" IN ('New', 'Done')),
+    PRIMARY KEY ("sha256")
+);
+INSERT INTO "file_new" ("sha256", "size", "filename", "status") SELECT "sha256", "size", "name", "status" FROM "file";
+DROP TABLE "file";
+ALTER TABLE "file_new" RENAME TO "file";
+CREATE INDEX "file_source_idx" ON "file" ("source");
+PRAGMA foreign_key_check;
+COMMIT;
+PRAGMA foreign_keys = ON;
+"#);
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Extended {
+    #[metadata(primary_key: true)]
+    sha256: String,
+    #[serde(alias = "name")]
+    filename: String,
+    note: Option<String>,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Original {
+    #[metadata(primary_key: true)]
+    sha256: String,
+    name: String,
+}
+
+#[test]
+fn sqlite_alter() {
+    let sql = Sql::new(Dialect::Sqlite);
+    let mut old = sql.tables(&Original::metadata()).unwrap();
+    old[0].name = "extended".to_owned();
+    let migration = sql.migrate_tables(&old, &sql.tables(&Extended::metadata()).unwrap());
+    assert!(!migration.is_destructive());
+    assert_eq!(migration.to_string(), r#"-- rename column extended.name to filename
+ALTER TABLE "extended" RENAME COLUMN "name" TO "filename";
+
+-- add column extended.note
+ALTER TABLE "extended" ADD COLUMN "note" TEXT;
+"#);
+    assert!(sql.migrate_tables(&old[..0], &old).steps[0].description == "create table extended");
+}
@@ -0,0 +1,189 @@
+#![cfg(feature = "template")]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use struct_metadata::template::{Format, Template};
+use struct_metadata::Described;
+
+
+/// Service configuration
+#[derive(Described, serde::Serialize, Default)]
+#[allow(dead_code)]
+struct Config {
+    /// Address to listen on
+    bind: String,
+    /// Number of worker threads
+    #[serde(default)]
+    workers: u32,
+    #[serde(default)]
+    level: Level,
+    /// Proxy used for outgoing requests
+    #[serde(default)]
+    proxy: Option<String>,
+    #[serde(default)]
+    log: Log,
+    upstreams: Vec<Upstream>,
+    #[serde(default)]
+    labels: HashMap<String, String>,
+}
+
+#[derive(Described, serde::Serialize, Default)]
+#[allow(dead_code)]
+enum Level {
+    Error,
+    #[default]
+    Info,
+}
+
+#[derive(Described, serde::Serialize, Default)]
+#[allow(dead_code)]
+struct Log {
+    /// Log file path
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    json: bool,
+}
+
+#[derive(Described, serde::Serialize, Default)]
+#[allow(dead_code)]
+struct Upstream {
+    host: String,
+    port: u16,
+}
+
+fn defaults() -> serde_json::Value {
+    serde_json::to_value(Config { workers: 4, ..Default::default() }).unwrap()
+}
+
+#[test]
+fn yaml() {
+    let output = Template::new(Format::Yaml).defaults(defaults()).generate(&Config::metadata());
+    assert_eq!(output, r#"# Service configuration
+
+# Address to listen on
+# Type: String, required
+bind: <String>
+# Number of worker threads
+# Type: u32
+workers: 4
+# One of: Error, Info
+level: "Info"
+# Proxy used for outgoing requests
+# Type: String
+# proxy: <String>
+log:
+  # Log file path
+  # Type: String
+  # path: <String>
+  # Type: bool
+  json: false
+# Type: Vec<Upstream>, required
+upstreams:
+  -
+    # Type: String, required
+    host: <String>
+    # Type: u16, required
+    port: <u16>
+# Type: HashMap<String, String>
+# labels:
+#   "<String>": <String>
+"#);
+}
+
+#[test]
+fn unknown_defaults_are_commented_out() {
+    let output = Template::new(Format::Yaml).generate(&Config::metadata());
+    assert!(output.contains("\n# workers: <u32>\n"));
+    assert!(output.contains("\n# level: <Error | Info>\n"));
+    assert!(output.contains("\nlog:\n  # Log file path\n  # Type: String\n  # path: <String>\n  # Type: bool\n  # json: <bool>\n"));
+    assert!(output.contains("\nbind: <String>\n"));
+}
+
+#[test]
+fn toml() {
+    let output = Template::new(Format::Toml).defaults(defaults()).generate(&Config::metadata());
+    assert_eq!(output, r#"# Service configuration
+
+# Address to listen on
+# Type: String, required
+bind = "<String>"
+# Number of worker threads
+# Type: u32
+workers = 4
+# One of: Error, Info
+level = "Info"
+# Proxy used for outgoing requests
+# Type: String
+# proxy = "<String>"
+
+[log]
+# Log file path
+# Type: String
+# path = "<String>"
+# Type: bool
+json = false
+
+# Type: Vec<Upstream>, required
+[[upstreams]]
+# Type: String, required
+host = "<String>"
+# Type: u16, required
+port = "<u16>"
+
+# Type: HashMap<String, String>
+# [labels]
+# "<String>" = "<String>"
+"#);
+}
+
+#[test]
+fn json5() {
+    let output = Template::new(Format::Json5).defaults(defaults()).generate(&Config::metadata());
+    assert_eq!(output, r#"// Service configuration
+
+{
+  // Address to listen on
+  // Type: String, required
+  bind: "<String>",
+  // Number of worker threads
+  // Type: u32
+  workers: 4,
+  // One of: Error, Info
+  level: "Info",
+  // Proxy used for outgoing requests
+  // Type: String
+  // proxy: "<String>",
+  log: {
+    // Log file path
+    // Type: String
+    // path: "<String>",
+    // Type: bool
+    json: false,
+  },
+  // Type: Vec<Upstream>, required
+  upstreams: [
+    {
+      // Type: String, required
+      host: "<String>",
+      // Type: u16, required
+      port: "<u16>",
+    },
+  ],
+  // Type: HashMap<String, String>
+  // labels: {
+  //   "<String>": "<String>",
+  // },
+}
+"#);
+}
+
+#[test]
+fn known_containers_are_written() {
+    let defaults = json!({"workers": 1, "labels": {"team": "core"}, "log": {"path": "/var/log/app.log"}});
+    let output = Template::new(Format::Toml).defaults(defaults).generate(&Config::metadata());
+    assert!(output.contains("\nlabels = { team = \"core\" }\n"), "{output}");
+    assert!(output.contains("\n# path = \"/var/log/app.log\"\n"), "{output}");
+}
@@ -0,0 +1,64 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use struct_metadata::Described;
+use struct_metadata::typescript::TypeScript;
+
+
+/// A scanned file
+///
+/// Stored once per hash
+#[derive(Described, serde::Serialize)]
+#[allow(dead_code)]
+struct File {
+    /// Content hash
+    sha256: String,
+    size: u64,
+    #[serde(rename = "mime-type")]
+    mime_type: Option<String>,
+    tags: Vec<Option<Tag>>,
+    scores: HashMap<String, f32>,
+    status: Status,
+    extra: Option<bool>,
+    #[serde(skip)]
+    cache: u32,
+}
+
+#[derive(Described, serde::Serialize)]
+#[allow(dead_code)]
+struct Tag(String);
+
+#[derive(Described, serde::Serialize)]
+#[allow(dead_code)]
+enum Status {
+    #[serde(rename = "new")]
+    New,
+    #[serde(rename = "done")]
+    Done,
+}
+
+#[test]
+fn declarations() {
+    assert_eq!(TypeScript::new().big_integer_type("string").generate([&File::metadata()]), r#"/**
+ * A scanned file
+ *
+ * Stored once per hash
+ */
+export interface File {
+    /** Content hash */
+    sha256: string;
+    size: string;
+    "mime-type"?: string | null;
+    tags: (Tag | null)[];
+    scores: Record<string, number>;
+    status: Status;
+    extra?: boolean | null;
+}
+
+export type Status = "new" | "done";
+
+export type Tag = string;
+"#);
+}
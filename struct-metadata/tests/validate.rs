@@ -0,0 +1,148 @@
+#![cfg(feature = "serde_json")]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use struct_metadata::{Described, Violation, ViolationKind};
+
+
+/// An order placed by a customer
+#[derive(Described)]
+#[allow(dead_code)]
+struct Order {
+    #[serde(alias = "number")]
+    id: u64,
+    items: Vec<Item>,
+    #[serde(default)]
+    priority: u8,
+    note: Option<String>,
+    state: State,
+    counts: HashMap<u16, i8>,
+    #[serde(skip_deserializing)]
+    total: u32,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Item {
+    #[metadata(max_length: 8)]
+    sku: String,
+    quantity: u32,
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+enum State {
+    Open,
+    #[serde(alias = "finished")]
+    Closed,
+}
+
+fn violation(path: &str, kind: ViolationKind) -> Violation {
+    Violation { path: path.to_owned(), kind }
+}
+
+#[test]
+fn valid() {
+    let value = json!({
+        "number": 5,
+        "items": [{"sku": "abc", "quantity": 2}],
+        "note": null,
+        "state": "finished",
+        "counts": {"1": -3},
+    });
+    assert_eq!(Order::metadata().validate(&value), vec![]);
+}
+
+#[test]
+fn violations() {
+    let value = json!({
+        "id": -1,
+        "items": [{"sku": 10, "quantity": 2, "extra": true}, "item"],
+        "priority": 300,
+        "state": "Lost",
+        "counts": {"70000": 200, "x": 1},
+        "colour": "red",
+    });
+    assert_eq!(Order::metadata().validate(&value), vec![
+        violation("$.id", ViolationKind::OutOfRange { value: "-1".to_owned(), expected: "u64".to_owned() }),
+        violation("$.items[0].sku", ViolationKind::WrongType { expected: "String".to_owned(), found: "number" }),
+        violation("$.items[0].extra", ViolationKind::UnknownField { field: "extra".to_owned() }),
+        violation("$.items[1]", ViolationKind::WrongType { expected: "Item".to_owned(), found: "string" }),
+        violation("$.priority", ViolationKind::OutOfRange { value: "300".to_owned(), expected: "u8".to_owned() }),
+        violation("$.state", ViolationKind::UnknownVariant { value: "Lost".to_owned(), expected: vec!["Open".to_owned(), "Closed".to_owned()] }),
+        violation("$.counts", ViolationKind::InvalidKey {
+            key: "70000".to_owned(),
+            problem: Box::new(ViolationKind::OutOfRange { value: "70000".to_owned(), expected: "u16".to_owned() }),
+        }),
+        violation("$.counts[\"70000\"]", ViolationKind::OutOfRange { value: "200".to_owned(), expected: "i8".to_owned() }),
+        violation("$.counts", ViolationKind::InvalidKey {
+            key: "x".to_owned(),
+            problem: Box::new(ViolationKind::WrongType { expected: "u16".to_owned(), found: "string" }),
+        }),
+        violation("$.colour", ViolationKind::UnknownField { field: "colour".to_owned() }),
+    ]);
+}
+
+#[derive(Described)]
+#[allow(dead_code)]
+struct Sizes {
+    small: u8,
+    large: u64,
+    huge: u128,
+    signed: i128,
+}
+
+#[test]
+fn integer_ranges() {
+    let value: serde_json::Value = serde_json::from_str(r#"{
+        "small": 1e20,
+        "large": 18446744073709551616,
+        "huge": 100000000000000000000,
+        "signed": -100000000000000000000
+    }"#).unwrap();
+    let messages: Vec<_> = Sizes::metadata().validate(&value).iter().map(|violation| violation.to_string()).collect();
+    assert_eq!(messages, vec![
+        "$.small: 1e20 is out of range for u8",
+        "$.large: 1.8446744073709552e19 is out of range for u64",
+    ]);
+
+    let value = json!({"small": 2.5, "large": 2.0, "huge": -1, "signed": 1});
+    let messages: Vec<_> = Sizes::metadata().validate(&value).iter().map(|violation| violation.to_string()).collect();
+    assert_eq!(messages, vec![
+        "$.small: expected u8, found number",
+        "$.large: expected u64, found number",
+        "$.huge: -1 is out of range for u128",
+    ]);
+}
+
+#[test]
+fn missing_fields() {
+    let violations = Order::metadata().validate(&json!({"items": []}));
+    let messages: Vec<_> = violations.iter().map(|violation| violation.to_string()).collect();
+    assert_eq!(messages, vec![
+        "$: missing field id",
+        "$: missing field state",
+        "$: missing field counts",
+    ]);
+    assert_eq!(Order::metadata().validate(&json!([])), vec![
+        violation("$", ViolationKind::WrongType { expected: "Order".to_owned(), found: "array" }),
+    ]);
+}
+
+#[test]
+fn hook() {
+    let descriptor = Item::metadata();
+    let validator = descriptor.validator().with_hook(|metadata, value| {
+        let limit = metadata.get("max_length").and_then(|limit| limit.parse::<usize>().ok());
+        match (limit, value.as_str()) {
+            (Some(limit), Some(text)) if text.len() > limit => vec![format!("longer than {limit} characters")],
+            _ => vec![],
+        }
+    });
+    assert_eq!(validator.validate(&json!({"sku": "short", "quantity": 1})), vec![]);
+    assert_eq!(validator.validate(&json!({"sku": "much too long", "quantity": 1})), vec![
+        violation("$.sku", ViolationKind::Constraint { message: "longer than 8 characters".to_owned() }),
+    ]);
+}